@@ -20,6 +20,7 @@ pub struct PlayerBundle {
 
 impl PlayerBundle {
     pub fn new(
+        name: String,
         life_total: u32,
         armor_value: u32,
         initiative: u32,
@@ -33,7 +34,7 @@ impl PlayerBundle {
         Self {
             player: Player,
             combatant: Combatant,
-            character_type: CharacterType("Spieler".to_string()),
+            character_type: CharacterType(name),
             health: Health::new(life_total),
             armor: Armor(armor_value),
             initiative: Initiative::new(initiative),
@@ -71,6 +71,21 @@ impl SpecialAbilities {
 #[derive(Component)]
 pub struct CharacterType(pub String);
 
+// Sprite sheet to draw the character with in combat (from enemies.ron/classes.ron, see SpriteSheets)
+// Frame ranges are (first, last) indices in the atlas
+#[derive(Component, Clone, Debug)]
+pub struct SpriteSheet {
+    pub path: String,
+    pub tile_size: UVec2,
+    pub columns: u32,
+    pub rows: u32,
+    pub idle: (usize, usize),
+    pub attack: (usize, usize),
+    pub hit: (usize, usize),
+    pub death: (usize, usize),
+    pub fallback_color: Color, // drawn as rectangle if the texture is missing
+}
+
 #[derive(Component)]
 pub struct Player;
 
@@ -6,6 +6,8 @@ pub struct QueuedAction {
     pub target: Option<Entity>, // Who to attack
     pub finte_level: u32,
     pub wuchtschlag_level: u32,
+    pub bonus_damage: u32, // from boss special attacks
+    pub bonus_dice: u32,
 }
 
 impl QueuedAction {
@@ -14,10 +16,16 @@ impl QueuedAction {
             target: None,
             finte_level: 0,
             wuchtschlag_level: 0,
+            bonus_damage: 0,
+            bonus_dice: 0,
         }
     }
 }
 
+// Marker component - player wants to flee this turn
+#[derive(Component)]
+pub struct FleeAttempt;
+
 // Marker component - entity is currently in combat
 #[derive(Component)]
 pub struct InCombat;
\ No newline at end of file
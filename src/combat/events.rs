@@ -18,14 +18,22 @@ pub struct EnemyTurnEvent {
     pub enemy_entity: Entity,
 }
 
+#[derive(Message)]
+pub struct TurnQueueChangedEvent {
+    pub joined: Vec<Entity>,
+    pub left: Vec<Entity>,
+}
+
 #[derive(Message)]  // Changed from Event
 pub struct CombatMessageEvent {
     pub message: String,
     pub message_type: MessageType,
     pub delay_ms: u64,
+    pub actor: Option<String>, // who did it (for the structured log)
+    pub target: Option<String>, // who it was done to
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MessageType {
     RoundStart,
     PlayerAction,
@@ -36,9 +44,27 @@ pub enum MessageType {
     Defense,
     CombatStart,
     CombatEnd,
+    Flee,
+}
+
+// Result of one attack, for visual feedback (damage numbers, popups)
+#[derive(Message)]
+pub struct AttackResolvedEvent {
+    pub attacker: Entity,
+    pub target: Entity,
+    pub outcome: AttackOutcome,
+    pub heavy: bool, // hit for at least a quarter of the target's max HP
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttackOutcome {
+    Hit { damage: u32 },
+    Parried,
+    Missed,
 }
 
 #[derive(Message)]  // Changed from Event
 pub struct CombatEndEvent {
     pub player_won: bool,
+    pub player_fled: bool,
 }
\ No newline at end of file
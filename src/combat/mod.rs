@@ -21,20 +21,36 @@ pub fn plugin(app: &mut App) {
         .add_message::<RoundStartEvent>()
         .add_message::<PlayerTurnEvent>()
         .add_message::<EnemyTurnEvent>()
+        .add_message::<TurnQueueChangedEvent>()
         .add_message::<CombatMessageEvent>()
+        .add_message::<AttackResolvedEvent>()
         .add_message::<CombatEndEvent>()
 
         // add resources
         .init_resource::<BattleState>()
+        .init_resource::<CombatRules>()
+        .init_resource::<CombatMessageQueue>()
+
+        .add_systems(Startup, load_combat_rules_system)
 
         // Systems that run when in combat state
         .add_systems(Update, (
             process_turn_system,
             enemy_ai_system,
             execute_attack_system,
+            execute_flee_system,
             check_victory_system,
+            announce_turn_queue_changes_system,
         ).run_if(in_state(GameState::Combat)))
 
+        // re-rolls are applied at the next rebuild
+        .add_systems(Update, reroll_initiative_system
+            .before(process_turn_system)
+            .run_if(in_state(GameState::Combat)))
+
         // system that runs when entering combat state
-        .add_systems(OnEnter(GameState::Combat), start_combat_system);
+        .add_systems(OnEnter(GameState::Combat), start_combat_system)
+
+        // the player stays around after the fight, unresolved actions don't
+        .add_systems(OnExit(GameState::Combat), clear_pending_actions_system);
 }
\ No newline at end of file
@@ -1,4 +1,12 @@
+use std::collections::VecDeque;
+
 use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::config::Config;
+
+use super::events::MessageType;
 
 // Current state of the battle
 #[derive(Resource, Default)]
@@ -8,6 +16,10 @@ pub struct BattleState {
     pub current_turn_index: usize, // Whose turn it is right now
     pub waiting_for_player_input: bool, // Waiting for player to choose?
     pub combat_active: bool, // Is combat happening?
+    pub flee_allowed: bool, // Does the room allow fleeing?
+    pub pending_joins: Vec<Entity>, // Join the queue at the next round
+    pub pending_leaves: Vec<Entity>, // Leave the queue at the next round
+    pub rebuild_queue: bool, // New round started, re-sort before the first turn
 }
 
 impl BattleState {
@@ -26,4 +38,123 @@ impl BattleState {
     pub fn is_round_over(&self) -> bool {
         self.current_turn_index >= self.turn_queue.len()
     }
-}
\ No newline at end of file
+
+    // Add a combatant mid-fight, gets its first turn next round
+    pub fn add_combatant(&mut self, entity: Entity) {
+        self.pending_leaves.retain(|&e| e != entity);
+        if !self.turn_queue.contains(&entity) && !self.pending_joins.contains(&entity) {
+            self.pending_joins.push(entity);
+        }
+    }
+
+    // Remove a combatant mid-fight, skips its remaining turns right away
+    pub fn remove_combatant(&mut self, entity: Entity) {
+        self.pending_joins.retain(|&e| e != entity);
+        if self.turn_queue.contains(&entity) && !self.pending_leaves.contains(&entity) {
+            self.pending_leaves.push(entity);
+        }
+    }
+
+    pub fn is_leaving(&self, entity: Entity) -> bool {
+        self.pending_leaves.contains(&entity)
+    }
+
+    // Apply joins and leaves at a round boundary, returns (joined, left)
+    pub fn apply_pending_changes(&mut self) -> (Vec<Entity>, Vec<Entity>) {
+        let joined = std::mem::take(&mut self.pending_joins);
+        let left = std::mem::take(&mut self.pending_leaves);
+        self.turn_queue.retain(|e| !left.contains(e));
+        self.turn_queue.extend(joined.iter().copied());
+        (joined, left)
+    }
+}
+
+// Optional combat rules, loaded from assets/config/combat_rules.ron
+#[derive(Resource, Deserialize)]
+pub struct CombatRules {
+    pub reroll_initiative_each_round: bool, // w6 + base every round instead of once
+    pub tiebreaker: InitiativeTiebreaker,
+}
+
+impl Config for CombatRules {
+    fn from_ron(content: &str) -> Result<Self, String> {
+        ron::from_str(content).map_err(|err| err.to_string())
+    }
+}
+
+impl Default for CombatRules {
+    fn default() -> Self {
+        Self {
+            reroll_initiative_each_round: false,
+            tiebreaker: InitiativeTiebreaker::PlayerFirst,
+        }
+    }
+}
+
+// Decides equal initiative after base initiative is equal too
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum InitiativeTiebreaker {
+    PlayerFirst,
+    EnemiesFirst,
+    Random,
+}
+
+// One combatant when sorting the turn order
+pub struct TurnOrderEntry {
+    pub entity: Entity,
+    pub initiative: u32, // randomized initiative
+    pub base: u32,
+    pub is_player: bool,
+}
+
+// Sort by initiative, then base initiative, then tiebreaker (highest first)
+pub fn sort_turn_order(entries: Vec<TurnOrderEntry>, tiebreaker: InitiativeTiebreaker) -> Vec<Entity> {
+    let mut rng = rand::rng();
+    let mut keyed: Vec<_> = entries
+        .into_iter()
+        .map(|entry| {
+            let tie = match tiebreaker {
+                InitiativeTiebreaker::PlayerFirst => entry.is_player as u32,
+                InitiativeTiebreaker::EnemiesFirst => !entry.is_player as u32,
+                InitiativeTiebreaker::Random => rng.random_range(0..1000),
+            };
+            ((entry.initiative, entry.base, tie), entry.entity)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.cmp(&a.0));
+    keyed.into_iter().map(|(_, entity)| entity).collect()
+}
+
+// Combat message that has not been shown in the log yet
+pub struct PendingCombatMessage {
+    pub message: String,
+    pub message_type: MessageType,
+    pub delay_ms: u64,
+    pub round: u32,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+}
+
+// Messages are revealed one after another, turns wait until all are shown
+#[derive(Resource)]
+pub struct CombatMessageQueue {
+    pub pending: VecDeque<PendingCombatMessage>,
+    pub elapsed_ms: f32, // time waited for the front message
+    pub speed: f32, // playback speed, from the combat speed setting
+}
+
+impl Default for CombatMessageQueue {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            elapsed_ms: 0.0,
+            speed: 1.0,
+        }
+    }
+}
+
+impl CombatMessageQueue {
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
@@ -2,13 +2,23 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use crate::character::*;
+use crate::config::load_config;
+use crate::localization::Localization;
 use super::components::*;
 use super::resources::*;
 use super::events::*;
 
+// System that loads the combat rules config, keeps defaults if it is missing
+pub fn load_combat_rules_system(mut rules: ResMut<CombatRules>) {
+    if let Some(loaded) = load_config("assets/config/combat_rules.ron") {
+        *rules = loaded;
+    }
+}
+
 // System that starts a combat encounter
 pub fn start_combat_system(
     mut battle_state: ResMut<BattleState>,
+    rules: Res<CombatRules>,
     mut combat_start_events: MessageWriter<CombatStartEvent>,
     // Use ParamSet to avoid conflicts
     mut queries: ParamSet<(
@@ -37,25 +47,35 @@ pub fn start_combat_system(
     }
 
     // Build turn order based on initiative
-    let mut all_combatants: Vec<(Entity, u32)> = Vec::new();
+    let mut all_combatants: Vec<TurnOrderEntry> = Vec::new();
 
     // Collect player initiatives
     for (entity, initiative) in queries.p0().iter() {
-        all_combatants.push((entity, initiative.randomized));
+        all_combatants.push(TurnOrderEntry {
+            entity,
+            initiative: initiative.randomized,
+            base: initiative.base,
+            is_player: true,
+        });
     }
 
     // Collect enemy initiatives
     for (entity, initiative) in queries.p1().iter() {
-        all_combatants.push((entity, initiative.randomized));
+        all_combatants.push(TurnOrderEntry {
+            entity,
+            initiative: initiative.randomized,
+            base: initiative.base,
+            is_player: false,
+        });
     }
 
-    // Sort by initative (highest first)
-    all_combatants.sort_by(|a, b| b.1.cmp(&a.1));
-
-    // Update battle state with turn order
-    battle_state.turn_queue = all_combatants.iter().map(|(e, _)| *e).collect();
+    // Update battle state with turn order (highest initiative first)
+    battle_state.turn_queue = sort_turn_order(all_combatants, rules.tiebreaker);
     battle_state.current_turn_index = 0;
     battle_state.current_round = 1;
+    battle_state.pending_joins.clear();
+    battle_state.pending_leaves.clear();
+    battle_state.rebuild_queue = false;
 
     let enemy_count = queries.p1().iter().count();
 
@@ -67,29 +87,78 @@ pub fn start_combat_system(
 
 pub fn process_turn_system(
     mut battle_state: ResMut<BattleState>,
+    rules: Res<CombatRules>,
+    message_queue: Res<CombatMessageQueue>,
     mut round_events: MessageWriter<RoundStartEvent>,
     mut player_turn_events: MessageWriter<PlayerTurnEvent>,
     mut enemy_turn_events: MessageWriter<EnemyTurnEvent>,
+    mut queue_events: MessageWriter<TurnQueueChangedEvent>,
     player_query: Query<Entity, With<Player>>,
     enemy_query: Query<Entity, With<Enemy>>,
+    combatant_query: Query<(&Initiative, &Health)>,
 ) {
     if !battle_state.combat_active {
         return;
     }
 
+    // Wait until the log has caught up with the last action
+    if !message_queue.is_empty() {
+        return;
+    }
+
     // Check if round is over
     if battle_state.is_round_over() {
         // Start new round
         battle_state.current_round += 1;
         battle_state.current_turn_index = 0;
+        battle_state.rebuild_queue = true;
         round_events.write(RoundStartEvent {
             round_number: battle_state.current_round,
         });
         return;
     }
 
+    // Round boundary: systems reacting to RoundStartEvent had their chance
+    // to add or remove combatants, so rebuild the queue before the first turn
+    if battle_state.rebuild_queue {
+        battle_state.rebuild_queue = false;
+
+        let (joined, left) = battle_state.apply_pending_changes();
+        if !joined.is_empty() || !left.is_empty() {
+            queue_events.write(TurnQueueChangedEvent { joined, left });
+        }
+
+        // Re-sort by initiative (may have been re-rolled this round)
+        let entries = battle_state.turn_queue
+            .iter()
+            .map(|&entity| {
+                let (initiative, base) = combatant_query
+                    .get(entity)
+                    .map(|(initiative, _)| (initiative.randomized, initiative.base))
+                    .unwrap_or((0, 0));
+                TurnOrderEntry {
+                    entity,
+                    initiative,
+                    base,
+                    is_player: player_query.get(entity).is_ok(),
+                }
+            })
+            .collect();
+        battle_state.turn_queue = sort_turn_order(entries, rules.tiebreaker);
+        return;
+    }
+
     // Get current turn entity
     if let Some(current_entity) = battle_state.current_turn() {
+        // Skip combatants that left, died or no longer exist
+        let can_act = combatant_query
+            .get(current_entity)
+            .is_ok_and(|(_, health)| health.is_alive());
+        if !can_act || battle_state.is_leaving(current_entity) {
+            battle_state.advance_turn();
+            return;
+        }
+
         // Check if its a player
         if player_query.get(current_entity).is_ok() {
             if !battle_state.waiting_for_player_input {
@@ -106,11 +175,81 @@ pub fn process_turn_system(
     }
 }
 
+// system that re-rolls initiative every round (optional rule)
+pub fn reroll_initiative_system(
+    rules: Res<CombatRules>,
+    mut round_events: MessageReader<RoundStartEvent>,
+    mut message_events: MessageWriter<CombatMessageEvent>,
+    mut combatant_query: Query<(&CharacterType, &mut Initiative, &Health), With<Combatant>>,
+    loc: Res<Localization>,
+) {
+    for event in round_events.read() {
+        if !rules.reroll_initiative_each_round {
+            continue;
+        }
+
+        // w6 + base initiative, queue is re-sorted before the first turn
+        let mut rng = rand::rng();
+        let mut rolls = Vec::new();
+        for (character_type, mut initiative, health) in combatant_query.iter_mut() {
+            initiative.randomized = initiative.base + rng.random_range(1..=6);
+            if health.is_alive() {
+                rolls.push(format!("{} {}", character_type.0, initiative.randomized));
+            }
+        }
+
+        message_events.write(CombatMessageEvent {
+            message: loc.t_with("combat.initiative_rerolled", &[
+                ("round", &event.round_number),
+                ("rolls", &rolls.join(", ")),
+            ]),
+            message_type: MessageType::RoundStart,
+            delay_ms: 0,
+            actor: None,
+            target: None,
+        });
+    }
+}
+
+// system that announces changes to the turn order in the combat log
+pub fn announce_turn_queue_changes_system(
+    mut queue_events: MessageReader<TurnQueueChangedEvent>,
+    mut message_events: MessageWriter<CombatMessageEvent>,
+    combatant_query: Query<&CharacterType>,
+    loc: Res<Localization>,
+) {
+    for event in queue_events.read() {
+        for &entity in &event.joined {
+            if let Ok(character_type) = combatant_query.get(entity) {
+                message_events.write(CombatMessageEvent {
+                    message: loc.t_with("combat.joined_turn_order", &[("name", &character_type.0)]),
+                    message_type: MessageType::RoundStart,
+                    delay_ms: 0,
+                    actor: Some(character_type.0.clone()),
+                    target: None,
+                });
+            }
+        }
+        for &entity in &event.left {
+            if let Ok(character_type) = combatant_query.get(entity) {
+                message_events.write(CombatMessageEvent {
+                    message: loc.t_with("combat.left_turn_order", &[("name", &character_type.0)]),
+                    message_type: MessageType::RoundStart,
+                    delay_ms: 0,
+                    actor: Some(character_type.0.clone()),
+                    target: None,
+                });
+            }
+        }
+    }
+}
+
 // system that executes an attack
 pub fn execute_attack_system(
     mut commands: Commands,
     mut battle_state: ResMut<BattleState>,
     mut message_events: MessageWriter<CombatMessageEvent>,
+    mut attack_events: MessageWriter<AttackResolvedEvent>,
     attacker_query: Query<(
         &CharacterType,
         &Attack,
@@ -126,6 +265,7 @@ pub fn execute_attack_system(
     )>,
     action_query: Query<(Entity, &QueuedAction)>,
     player_query: Query<Entity, With<Player>>,
+    loc: Res<Localization>,
 ) {
     // Find entities with queued actions
     for (attacker_entity, action) in action_query.iter() {
@@ -166,11 +306,20 @@ pub fn execute_attack_system(
             .saturating_sub(finte)
             .saturating_sub(wuchtschlag * 2);
 
+        // player and enemy actions are shown differently in the log
+        let action_type = if player_query.get(attacker_entity).is_ok() {
+            MessageType::PlayerAction
+        } else {
+            MessageType::EnemyAction
+        };
+
         // write attack start message
         message_events.write(CombatMessageEvent {
-            message: format!("{} startet den Angriff!", attacker_type.0),
-            message_type: MessageType::PlayerAction,
-            delay_ms: 0,
+            message: loc.t_with("combat.attack_started", &[("name", &attacker_type.0)]),
+            message_type: action_type,
+            delay_ms: 600,
+            actor: Some(attacker_type.0.clone()),
+            target: Some(defender_type.0.clone()),
         });
 
         // Check if attack hits
@@ -178,13 +327,13 @@ pub fn execute_attack_system(
             // attack hits
             let mut total_damage = damage_stat.0;
 
-            // roll damage dice
-            for _ in 0..dice_roll.0 {
+            // roll damage dice (boss special attacks roll extra dice)
+            for _ in 0..dice_roll.0 + action.bonus_dice {
                 total_damage += rng.random_range(1..=6);
             }
 
-            // bonus damage from wuchtschlag
-            total_damage += wuchtschlag * 2;
+            // bonus damage from wuchtschlag and special attacks
+            total_damage += wuchtschlag * 2 + action.bonus_damage;
 
             // defender tries defending
             let defense_roll = rng.random_range(1..=20);
@@ -197,24 +346,59 @@ pub fn execute_attack_system(
                 defender_health.take_damage(actual_damage, 0);
 
                 message_events.write(CombatMessageEvent {
-                    message: format!("{} nimmt {} Schaden!", defender_type.0, actual_damage),
+                    message: loc.t_with("combat.damage_taken", &[
+                        ("name", &defender_type.0),
+                        ("damage", &actual_damage),
+                    ]),
                     message_type: MessageType::Damage,
                     delay_ms: 300,
+                    actor: Some(attacker_type.0.clone()),
+                    target: Some(defender_type.0.clone()),
+                });
+
+                attack_events.write(AttackResolvedEvent {
+                    attacker: attacker_entity,
+                    target: target_entity,
+                    outcome: AttackOutcome::Hit { damage: actual_damage },
+                    heavy: actual_damage * 4 >= defender_health.max,
                 });
+
+                // dead combatants leave the turn order at the next round
+                if !defender_health.is_alive() {
+                    battle_state.remove_combatant(target_entity);
+                }
             } else {
                 // defense success
                 message_events.write(CombatMessageEvent {
-                    message: format!("{} parriert erfolgreich!", defender_type.0),
+                    message: loc.t_with("combat.parried", &[("name", &defender_type.0)]),
                     message_type: MessageType::Defense,
                     delay_ms: 200,
+                    actor: Some(attacker_type.0.clone()),
+                    target: Some(defender_type.0.clone()),
+                });
+
+                attack_events.write(AttackResolvedEvent {
+                    attacker: attacker_entity,
+                    target: target_entity,
+                    outcome: AttackOutcome::Parried,
+                    heavy: false,
                 });
             }
         } else {
             // attack missed
             message_events.write(CombatMessageEvent {
-                    message: format!("{} scheiterte anzugreifen...", attacker_type.0),
-                    message_type: MessageType::PlayerAction,
-                    delay_ms: 0,
+                    message: loc.t_with("combat.attack_missed", &[("name", &attacker_type.0)]),
+                    message_type: action_type,
+                    delay_ms: 300,
+                    actor: Some(attacker_type.0.clone()),
+                    target: Some(defender_type.0.clone()),
+            });
+
+            attack_events.write(AttackResolvedEvent {
+                attacker: attacker_entity,
+                target: target_entity,
+                outcome: AttackOutcome::Missed,
+                heavy: false,
             });
         }
 
@@ -231,6 +415,82 @@ pub fn execute_attack_system(
     }
 }
 
+// system that resolves a flee attempt of the player
+pub fn execute_flee_system(
+    mut commands: Commands,
+    mut battle_state: ResMut<BattleState>,
+    mut message_events: MessageWriter<CombatMessageEvent>,
+    mut combat_end_events: MessageWriter<CombatEndEvent>,
+    player_query: Query<(Entity, &CharacterType, &Initiative), (With<Player>, With<FleeAttempt>)>,
+    enemy_query: Query<(&CharacterType, &Initiative, &Health), With<Enemy>>,
+    loc: Res<Localization>,
+) {
+    let Ok((player_entity, player_type, player_initiative)) = player_query.single() else {
+        return;
+    };
+
+    // remove flee attempt (handled now)
+    commands.entity(player_entity).remove::<FleeAttempt>();
+
+    // some rooms don't let you escape, turn is not used up
+    if !battle_state.flee_allowed {
+        message_events.write(CombatMessageEvent {
+            message: loc.t("combat.flee_forbidden"),
+            message_type: MessageType::Flee,
+            delay_ms: 0,
+            actor: Some(player_type.0.clone()),
+            target: None,
+        });
+        return;
+    }
+
+    // fastest living enemy decides if the player gets away
+    let Some((fastest_type, fastest_initiative, _)) = enemy_query
+        .iter()
+        .filter(|(_, _, health)| health.is_alive())
+        .max_by_key(|(_, initiative, _)| initiative.base)
+    else {
+        return;
+    };
+
+    // both roll w6 + base initiative
+    let mut rng = rand::rng();
+    let player_roll = player_initiative.base + rng.random_range(1..=6);
+    let enemy_roll = fastest_initiative.base + rng.random_range(1..=6);
+
+    if player_roll > enemy_roll {
+        // flee success, combat ends without reward
+        message_events.write(CombatMessageEvent {
+            message: loc.t_with("combat.flee_success", &[
+                ("player_roll", &player_roll),
+                ("enemy_roll", &enemy_roll),
+            ]),
+            message_type: MessageType::Flee,
+            delay_ms: 400,
+            actor: Some(player_type.0.clone()),
+            target: Some(fastest_type.0.clone()),
+        });
+        battle_state.combat_active = false;
+        combat_end_events.write(CombatEndEvent { player_won: false, player_fled: true });
+    } else {
+        // flee failed, turn is lost
+        message_events.write(CombatMessageEvent {
+            message: loc.t_with("combat.flee_failed", &[
+                ("name", &fastest_type.0),
+                ("enemy_roll", &enemy_roll),
+                ("player_roll", &player_roll),
+            ]),
+            message_type: MessageType::Flee,
+            delay_ms: 400,
+            actor: Some(player_type.0.clone()),
+            target: Some(fastest_type.0.clone()),
+        });
+        battle_state.advance_turn();
+    }
+
+    battle_state.waiting_for_player_input = false;
+}
+
 pub fn enemy_ai_system(
     mut commands: Commands,
     mut enemy_turn_events: MessageReader<EnemyTurnEvent>,
@@ -260,6 +520,8 @@ pub fn enemy_ai_system(
             target: Some(player_entity),
             finte_level: finte,
             wuchtschlag_level: wuchtschlag,
+            bonus_damage: 0,
+            bonus_dice: 0,
         });
     }
 }
@@ -279,7 +541,7 @@ pub fn check_victory_system(
     if let Ok(player_health) = player_query.single() {
         if !player_health.is_alive() {
             battle_state.combat_active = false;
-            combat_end_events.write(CombatEndEvent { player_won: false });
+            combat_end_events.write(CombatEndEvent { player_won: false, player_fled: false });
         }
     };
 
@@ -287,6 +549,13 @@ pub fn check_victory_system(
     let enemies_alive = enemy_query.iter().any(|health| health.is_alive());
     if !enemies_alive {
         battle_state.combat_active = false;
-        combat_end_events.write(CombatEndEvent { player_won: true });
+        combat_end_events.write(CombatEndEvent { player_won: true, player_fled: false });
     }
-}
\ No newline at end of file
+}
+
+// drop actions the player queued but never got to resolve before the fight ended
+pub fn clear_pending_actions_system(mut commands: Commands, player_query: Query<Entity, With<Player>>) {
+    for entity in player_query.iter() {
+        commands.entity(entity).remove::<(QueuedAction, FleeAttempt)>();
+    }
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+// Anything that is read from a RON file in assets/config
+pub trait Config: Sized {
+    fn from_ron(content: &str) -> Result<Self, String>;
+}
+
+// Read and parse a config file, None if it is missing or invalid so the caller keeps its defaults
+pub fn load_config<T: Config>(path: &str) -> Option<T> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            warn!("Could not read {}: {}", path, err);
+            return None;
+        }
+    };
+    match T::from_ron(&content) {
+        Ok(loaded) => {
+            info!("Loaded {}", path);
+            Some(loaded)
+        }
+        Err(err) => {
+            warn!("Invalid config in {}: {}", path, err);
+            None
+        }
+    }
+}
@@ -8,4 +8,6 @@ pub enum GameState {
     Gameplay,
     Combat,
     Settings,
+    GameOver,
+    Victory,
 }
\ No newline at end of file
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+use crate::localization::Localization;
+
+// Everything the player can do, screens read these instead of raw keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+    SelectN(u8), // number keys, enemy index or maneuver level
+    LevelDown,
+    LevelUp,
+    Flee,
+    FastForward,
+    CycleSpeed,
+    ScrollUp,
+    ScrollDown,
+    ExportLog,
+    ToggleMap,
+    Camp,
+    DeleteChar, // text input, e.g. the seed
+}
+
+impl GameAction {
+    // Actions that can be rebound, in the order they are listed in the settings
+    pub const REBINDABLE: [GameAction; 17] = [
+        GameAction::Up,
+        GameAction::Down,
+        GameAction::Left,
+        GameAction::Right,
+        GameAction::Confirm,
+        GameAction::Back,
+        GameAction::LevelDown,
+        GameAction::LevelUp,
+        GameAction::Flee,
+        GameAction::FastForward,
+        GameAction::CycleSpeed,
+        GameAction::ScrollUp,
+        GameAction::ScrollDown,
+        GameAction::ExportLog,
+        GameAction::ToggleMap,
+        GameAction::Camp,
+        GameAction::DeleteChar,
+    ];
+
+    pub fn name(&self, loc: &Localization) -> String {
+        let key = match self {
+            GameAction::Up => "action.up",
+            GameAction::Down => "action.down",
+            GameAction::Left => "action.left",
+            GameAction::Right => "action.right",
+            GameAction::Confirm => "action.confirm",
+            GameAction::Back => "action.back",
+            GameAction::SelectN(n) => return loc.t_with("action.select_n", &[("n", n)]),
+            GameAction::LevelDown => "action.level_down",
+            GameAction::LevelUp => "action.level_up",
+            GameAction::Flee => "action.flee",
+            GameAction::FastForward => "action.fast_forward",
+            GameAction::CycleSpeed => "action.cycle_speed",
+            GameAction::ScrollUp => "action.scroll_up",
+            GameAction::ScrollDown => "action.scroll_down",
+            GameAction::ExportLog => "action.export_log",
+            GameAction::ToggleMap => "action.toggle_map",
+            GameAction::Camp => "action.camp",
+            GameAction::DeleteChar => "action.delete_char",
+        };
+        loc.t(key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Gamepad(GamepadButton),
+}
+
+impl InputBinding {
+    pub fn device(&self) -> InputDevice {
+        match self {
+            InputBinding::Key(_) => InputDevice::Keyboard,
+            InputBinding::Gamepad(_) => InputDevice::Gamepad,
+        }
+    }
+
+    // Name shown in button prompts
+    pub fn label(&self, loc: &Localization) -> String {
+        match self {
+            InputBinding::Key(key) => match key {
+                KeyCode::Enter => "ENTER".to_string(),
+                KeyCode::Escape => "ESC".to_string(),
+                KeyCode::Space => loc.t("key.space"),
+                KeyCode::Tab => "Tab".to_string(),
+                KeyCode::Backspace => "⌫".to_string(),
+                KeyCode::PageUp => loc.t("key.page_up"),
+                KeyCode::PageDown => loc.t("key.page_down"),
+                KeyCode::ArrowUp => "↑".to_string(),
+                KeyCode::ArrowDown => "↓".to_string(),
+                KeyCode::ArrowLeft => "←".to_string(),
+                KeyCode::ArrowRight => "→".to_string(),
+                other => {
+                    let name = format!("{:?}", other);
+                    name.trim_start_matches("Key").trim_start_matches("Digit").to_string()
+                }
+            },
+            InputBinding::Gamepad(button) => match button {
+                GamepadButton::South => "(A)".to_string(),
+                GamepadButton::East => "(B)".to_string(),
+                GamepadButton::West => "(X)".to_string(),
+                GamepadButton::North => "(Y)".to_string(),
+                GamepadButton::LeftTrigger => "LB".to_string(),
+                GamepadButton::RightTrigger => "RB".to_string(),
+                GamepadButton::LeftTrigger2 => "LT".to_string(),
+                GamepadButton::RightTrigger2 => "RT".to_string(),
+                GamepadButton::DPadUp => "▲".to_string(),
+                GamepadButton::DPadDown => "▼".to_string(),
+                GamepadButton::DPadLeft => "◄".to_string(),
+                GamepadButton::DPadRight => "►".to_string(),
+                GamepadButton::LeftThumb => "L3".to_string(),
+                GamepadButton::RightThumb => "R3".to_string(),
+                GamepadButton::Mode => "Home".to_string(),
+                other => format!("{:?}", other),
+            },
+        }
+    }
+}
+
+// Keys and buttons bound to each action
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct InputBindings {
+    pub bindings: HashMap<GameAction, Vec<InputBinding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use GameAction::*;
+        use InputBinding::{Gamepad as Pad, Key};
+
+        let mut bindings = HashMap::from([
+            (Up, vec![Key(KeyCode::ArrowUp), Pad(GamepadButton::DPadUp)]),
+            (Down, vec![Key(KeyCode::ArrowDown), Pad(GamepadButton::DPadDown)]),
+            (Left, vec![Key(KeyCode::ArrowLeft), Pad(GamepadButton::DPadLeft)]),
+            (Right, vec![Key(KeyCode::ArrowRight), Pad(GamepadButton::DPadRight)]),
+            (Confirm, vec![Key(KeyCode::Enter), Pad(GamepadButton::South)]),
+            (Back, vec![Key(KeyCode::Escape), Pad(GamepadButton::East)]),
+            (LevelDown, vec![Pad(GamepadButton::LeftTrigger)]),
+            (LevelUp, vec![Pad(GamepadButton::RightTrigger)]),
+            (Flee, vec![Key(KeyCode::KeyF), Pad(GamepadButton::North)]),
+            (FastForward, vec![Key(KeyCode::Space), Pad(GamepadButton::West)]),
+            (CycleSpeed, vec![Key(KeyCode::Tab), Pad(GamepadButton::Select)]),
+            (ScrollUp, vec![Key(KeyCode::PageUp), Pad(GamepadButton::LeftTrigger2)]),
+            (ScrollDown, vec![Key(KeyCode::PageDown), Pad(GamepadButton::RightTrigger2)]),
+            (ExportLog, vec![Key(KeyCode::KeyE), Pad(GamepadButton::Mode)]),
+            (ToggleMap, vec![Key(KeyCode::KeyM), Pad(GamepadButton::Start)]),
+            (Camp, vec![Key(KeyCode::KeyR), Pad(GamepadButton::RightThumb)]),
+            (DeleteChar, vec![Key(KeyCode::Backspace), Pad(GamepadButton::LeftThumb)]),
+        ]);
+
+        let digits = [
+            KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+            KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+        ];
+        for (n, key) in digits.into_iter().enumerate() {
+            bindings.insert(SelectN(n as u8), vec![Key(key)]);
+        }
+
+        Self { bindings }
+    }
+}
+
+// Outcome of binding a new key or button to an action
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RebindResult {
+    Bound,
+    Swapped(GameAction),  // the other action got the old binding
+    Conflict(GameAction), // number keys can't be taken
+}
+
+pub const BINDINGS_PATH: &str = "settings/keybindings.ron";
+
+impl InputBindings {
+    pub fn get(&self, action: GameAction) -> &[InputBinding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // Replace the binding of the same device, an action already using it gets ours
+    pub fn rebind(&mut self, action: GameAction, binding: InputBinding) -> RebindResult {
+        let previous = self.get(action)
+            .iter()
+            .find(|existing| existing.device() == binding.device())
+            .copied();
+        if previous == Some(binding) {
+            return RebindResult::Bound;
+        }
+
+        let others: Vec<GameAction> = self.bindings
+            .iter()
+            .filter(|(other, inputs)| **other != action && inputs.contains(&binding))
+            .map(|(other, _)| *other)
+            .collect();
+        if let Some(&number) = others.iter().find(|other| matches!(other, GameAction::SelectN(_))) {
+            return RebindResult::Conflict(number);
+        }
+
+        for other in &others {
+            let inputs = self.bindings.entry(*other).or_default();
+            inputs.retain(|input| *input != binding);
+            inputs.extend(previous);
+        }
+
+        let inputs = self.bindings.entry(action).or_default();
+        inputs.retain(|input| input.device() != binding.device());
+        inputs.push(binding);
+
+        match others.first() {
+            Some(other) => RebindResult::Swapped(*other),
+            None => RebindResult::Bound,
+        }
+    }
+
+    // Load saved bindings, actions missing in the file keep their defaults
+    pub fn load(path: &str) -> Self {
+        let mut bindings = Self::default();
+        match std::fs::read_to_string(path) {
+            Ok(content) => match ron::from_str::<InputBindings>(&content) {
+                Ok(loaded) => {
+                    bindings.bindings.extend(loaded.bindings);
+                    info!("Loaded key bindings from {}", path);
+                }
+                Err(err) => warn!("Invalid key bindings in {}: {}", path, err),
+            },
+            Err(_) => info!("No key bindings in {}, using defaults", path),
+        }
+        bindings
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)?;
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)
+    }
+}
+
+// System that loads the user's key bindings at startup
+pub fn load_input_bindings_system(mut bindings: ResMut<InputBindings>) {
+    *bindings = InputBindings::load(BINDINGS_PATH);
+}
+
+// Actions triggered this frame
+#[derive(Resource, Default)]
+pub struct ActionState {
+    just_pressed: Vec<GameAction>,
+}
+
+impl ActionState {
+    // Actions that don't come from a device, e.g. scripted input in tests
+    pub fn set_just_pressed(&mut self, actions: &[GameAction]) {
+        self.just_pressed = actions.to_vec();
+    }
+
+    pub fn just_pressed(&self, action: GameAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    // Number pressed this frame (SelectN), if any
+    pub fn selected_number(&self) -> Option<u8> {
+        self.just_pressed.iter().find_map(|action| match action {
+            GameAction::SelectN(n) => Some(*n),
+            _ => None,
+        })
+    }
+}
+
+// Device the player used last, decides which button names are shown
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputDevice {
+    #[default]
+    Keyboard,
+    Gamepad,
+}
+
+// Left stick direction of the last frame, flicks act like the d-pad
+#[derive(Default)]
+pub struct StickState {
+    direction: Option<GamepadButton>,
+}
+
+const STICK_THRESHOLD: f32 = 0.5;
+
+// Translate keyboard and gamepad input of this frame into actions
+pub fn update_action_state(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<InputBindings>,
+    mut actions: ResMut<ActionState>,
+    mut device: ResMut<InputDevice>,
+    mut stick_state: Local<StickState>,
+) {
+    let mut pad_pressed: Vec<GamepadButton> = Vec::new();
+    let mut stick = Vec2::ZERO;
+    for gamepad in gamepads.iter() {
+        pad_pressed.extend(gamepad.get_just_pressed().copied());
+        if gamepad.left_stick().length() > stick.length() {
+            stick = gamepad.left_stick();
+        }
+    }
+
+    let direction = if stick.length() < STICK_THRESHOLD {
+        None
+    } else if stick.x.abs() > stick.y.abs() {
+        Some(if stick.x > 0.0 { GamepadButton::DPadRight } else { GamepadButton::DPadLeft })
+    } else {
+        Some(if stick.y > 0.0 { GamepadButton::DPadUp } else { GamepadButton::DPadDown })
+    };
+    if direction != stick_state.direction {
+        stick_state.direction = direction;
+        if let Some(button) = direction {
+            pad_pressed.push(button);
+        }
+    }
+
+    actions.just_pressed = bindings.bindings
+        .iter()
+        .filter(|(_, inputs)| {
+            inputs.iter().any(|input| match input {
+                InputBinding::Key(key) => keyboard.just_pressed(*key),
+                InputBinding::Gamepad(button) => pad_pressed.contains(button),
+            })
+        })
+        .map(|(action, _)| *action)
+        .collect();
+
+    // Switch the on-screen prompts to the device that was used last
+    let new_device = if !pad_pressed.is_empty() {
+        InputDevice::Gamepad
+    } else if keyboard.get_just_pressed().next().is_some() || mouse.get_just_pressed().next().is_some() {
+        InputDevice::Keyboard
+    } else {
+        *device
+    };
+    if *device != new_device {
+        *device = new_device;
+    }
+}
+
+// Button names for on-screen prompts, follows the bindings and the last used device
+#[derive(SystemParam)]
+pub struct ActionPrompts<'w> {
+    bindings: Res<'w, InputBindings>,
+    device: Res<'w, InputDevice>,
+    loc: Res<'w, Localization>,
+}
+
+impl ActionPrompts<'_> {
+    pub fn label(&self, action: GameAction) -> String {
+        self.bindings.get(action)
+            .iter()
+            .find(|binding| binding.device() == *self.device)
+            .map(|binding| binding.label(&self.loc))
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    // Two actions shown as one prompt, e.g. "Bild↑/Bild↓"
+    pub fn pair(&self, first: GameAction, second: GameAction) -> String {
+        format!("{}/{}", self.label(first), self.label(second))
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.bindings.is_changed() || self.device.is_changed() || self.loc.is_changed()
+    }
+}
@@ -0,0 +1,222 @@
+use bevy::{input::InputSystems, prelude::*};
+use crate::game_state::GameState;
+use crate::world::data::PlayerTypeData;
+use crate::world::{random_seed, RunSetup};
+
+pub mod actions;
+
+pub use actions::*;
+
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<MenuSelection>()
+            .init_resource::<SeedInput>()
+            .init_resource::<CharacterSelection>()
+            .init_resource::<ShowMap>()
+            .init_resource::<InputBindings>()
+            .init_resource::<ActionState>()
+            .init_resource::<InputDevice>()
+            .add_systems(Startup, load_input_bindings_system)
+            .add_systems(PreUpdate, update_action_state.after(InputSystems))
+            .add_systems(Update, handle_menu_input.run_if(in_state(GameState::MainMenu)))
+            .add_systems(Update, handle_character_select_input.run_if(in_state(GameState::CharacterSelection)))
+            .add_systems(Update, handle_gameplay_input.run_if(in_state(GameState::Gameplay)));
+    }
+}
+
+// Menu input handling
+fn handle_menu_input(
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut menu_selection: ResMut<MenuSelection>,
+    mut seed_input: ResMut<SeedInput>,
+    mut run_setup: ResMut<RunSetup>,
+) {
+    if actions.just_pressed(GameAction::Up) {
+        menu_selection.previous();
+    }
+    if actions.just_pressed(GameAction::Down) {
+        menu_selection.next();
+    }
+    
+    // Seed for the random dungeon is typed in while its button is selected
+    if menu_selection.current() == MenuSelection::RANDOM_DUNGEON {
+        if let Some(digit) = actions.selected_number() {
+            seed_input.push_digit(digit);
+        }
+        if actions.just_pressed(GameAction::DeleteChar) {
+            seed_input.digits.pop();
+        }
+    }
+    
+    if actions.just_pressed(GameAction::Confirm) {
+        match menu_selection.current() {
+            MenuSelection::START => {
+                run_setup.seed = None;
+                next_state.set(GameState::CharacterSelection);
+            }
+            MenuSelection::RANDOM_DUNGEON => {
+                run_setup.seed = Some(seed_input.seed());
+                next_state.set(GameState::CharacterSelection);
+            }
+            MenuSelection::SETTINGS => next_state.set(GameState::Settings),
+            MenuSelection::QUIT => std::process::exit(0), // Exit game
+            _ => {}
+        }
+    }
+}
+
+// Character selection input
+fn handle_character_select_input(
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut char_selection: ResMut<CharacterSelection>,
+    mut run_setup: ResMut<RunSetup>,
+) {
+    if actions.just_pressed(GameAction::Up) {
+        char_selection.previous();
+    }
+    if actions.just_pressed(GameAction::Down) {
+        char_selection.next();
+    }
+    if actions.just_pressed(GameAction::Left) {
+        run_setup.difficulty = run_setup.difficulty.previous();
+    }
+    if actions.just_pressed(GameAction::Right) {
+        run_setup.difficulty = run_setup.difficulty.next();
+    }
+    if actions.just_pressed(GameAction::Confirm) {
+        if char_selection.current() == CharacterSelection::PERMADEATH {
+            run_setup.permadeath = !run_setup.permadeath;
+        } else {
+            // Character selected, start game
+            next_state.set(GameState::Gameplay);
+        }
+    }
+    if actions.just_pressed(GameAction::Back) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+// Gameplay/exploration input
+fn handle_gameplay_input(
+    actions: Res<ActionState>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut show_map: ResMut<ShowMap>,
+) {
+    if actions.just_pressed(GameAction::ToggleMap) {
+        show_map.0 = !show_map.0;
+    }
+    
+    // Confirm (start combat, next room) is handled by the world systems
+    
+    if actions.just_pressed(GameAction::Back) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+// Resources for UI state
+#[derive(Resource)]
+pub struct MenuSelection {
+    pub current: usize,
+    pub max: usize,
+}
+
+impl Default for MenuSelection {
+    fn default() -> Self {
+        Self { current: Self::START, max: Self::QUIT }
+    }
+}
+
+impl MenuSelection {
+    // Main menu buttons, top to bottom
+    pub const START: usize = 0;
+    pub const RANDOM_DUNGEON: usize = 1;
+    pub const SETTINGS: usize = 2;
+    pub const QUIT: usize = 3;
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+    
+    pub fn next(&mut self) {
+        self.current = (self.current + 1) % (self.max + 1);
+    }
+    
+    pub fn previous(&mut self) {
+        self.current = if self.current == 0 { self.max } else { self.current - 1 };
+    }
+}
+
+#[derive(Resource)]
+pub struct CharacterSelection {
+    pub current: usize,
+    pub max: usize,
+}
+
+impl Default for CharacterSelection {
+    fn default() -> Self {
+        Self { current: 0, max: Self::PERMADEATH } // Sword Fighter, Shield Fighter, permadeath toggle
+    }
+}
+
+impl CharacterSelection {
+    pub const PERMADEATH: usize = 2;
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+    
+    pub fn next(&mut self) {
+        self.current = (self.current + 1) % (self.max + 1);
+    }
+    
+    pub fn previous(&mut self) {
+        self.current = if self.current == 0 { self.max } else { self.current - 1 };
+    }
+    
+    pub fn get_player_type(&self) -> PlayerTypeData {
+        match self.current {
+            0 => PlayerTypeData::SwordFighter,
+            1 => PlayerTypeData::ShieldFighter,
+            _ => PlayerTypeData::SwordFighter,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ShowMap(pub bool);
+
+// Seed typed into the main menu for the random dungeon, starts out random
+#[derive(Resource)]
+pub struct SeedInput {
+    pub digits: String,
+}
+
+impl Default for SeedInput {
+    fn default() -> Self {
+        Self { digits: random_seed().to_string() }
+    }
+}
+
+impl SeedInput {
+    // Long enough to share, short enough to type
+    const MAX_DIGITS: usize = 9;
+    
+    pub fn push_digit(&mut self, digit: u8) {
+        if self.digits.len() < Self::MAX_DIGITS {
+            self.digits.push(char::from(b'0' + digit));
+        }
+    }
+    
+    // An empty field rolls a new seed
+    pub fn seed(&mut self) -> u64 {
+        if self.digits.is_empty() {
+            self.digits = random_seed().to_string();
+        }
+        self.digits.parse().unwrap_or(0)
+    }
+}
@@ -4,12 +4,15 @@ mod game_state;
 
 // import plugin modules
 mod prelude;
-mod input;
+pub mod config;
+pub mod input;
 mod camera;
-mod combat;
-mod character;
-mod world;
-mod ui;
+pub mod combat;
+pub mod character;
+pub mod world;
+pub mod ui;
+pub mod settings;
+pub mod localization;
 
 pub use game_state::GameState;
 pub use input::InputPlugin;
@@ -24,6 +27,8 @@ impl Plugin for GamePlugin {
             .add_plugins(combat::plugin)
             .add_plugins(character::plugin)
             .add_plugins(ui::plugin)
+            .add_plugins(settings::plugin)
+            .add_plugins(localization::plugin)
             .add_plugins(world::plugin);
     }
 }
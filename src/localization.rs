@@ -0,0 +1,108 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use bevy::prelude::*;
+use crate::settings::GameSettings;
+
+pub use crate::settings::Language;
+
+// One string table per language, e.g. assets/locale/de.ron
+pub const LOCALE_DIR: &str = "assets/locale";
+
+pub fn plugin(app: &mut App) {
+    // Loaded right away, the first menu is spawned before the Startup systems run
+    app
+        .insert_resource(Localization::load(LOCALE_DIR))
+        .add_systems(Update, (
+            sync_language_system.run_if(resource_changed::<GameSettings>),
+            update_localized_texts.run_if(resource_changed::<Localization>),
+        ).chain());
+}
+
+// Player-facing strings keyed by id, e.g. "menu.start"
+#[derive(Resource)]
+pub struct Localization {
+    pub language: Language,
+    tables: HashMap<Language, HashMap<String, String>>,
+}
+
+impl Localization {
+    pub fn load(dir: &str) -> Self {
+        let mut tables = HashMap::new();
+        for language in Language::ALL {
+            let path = format!("{}/{}.ron", dir, language.code());
+            let table = match std::fs::read_to_string(&path) {
+                Ok(content) => match ron::from_str::<HashMap<String, String>>(&content) {
+                    Ok(table) => {
+                        info!("Loaded {} strings from {}", table.len(), path);
+                        table
+                    }
+                    Err(err) => {
+                        warn!("Invalid string table in {}: {}", path, err);
+                        HashMap::new()
+                    }
+                },
+                Err(err) => {
+                    warn!("Could not read {}: {}", path, err);
+                    HashMap::new()
+                }
+            };
+            tables.insert(language, table);
+        }
+        Self { language: Language::default(), tables }
+    }
+
+    pub fn has_key(&self, language: Language, key: &str) -> bool {
+        self.tables.get(&language).is_some_and(|table| table.contains_key(key))
+    }
+
+    // Text for the current language, falls back to German and then to the key itself
+    pub fn t(&self, key: &str) -> String {
+        [self.language, Language::default()]
+            .iter()
+            .find_map(|language| self.tables.get(language).and_then(|table| table.get(key)))
+            .cloned()
+            .unwrap_or_else(|| {
+                warn!("Missing string \"{}\"", key);
+                key.to_string()
+            })
+    }
+
+    // Text with "{name}" placeholders replaced by the given arguments
+    pub fn t_with(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.t(key);
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+
+    // Keys that exist in some table but not in the table of `language`
+    pub fn missing_keys(&self, language: Language) -> Vec<String> {
+        let all_keys: BTreeSet<&String> = self.tables.values().flat_map(|table| table.keys()).collect();
+        all_keys.into_iter()
+            .filter(|key| !self.has_key(language, key))
+            .cloned()
+            .collect()
+    }
+}
+
+// Static UI label that follows the language setting
+#[derive(Component)]
+pub struct LocalizedText(pub &'static str);
+
+// The language is picked up from the settings once they are loaded
+fn sync_language_system(settings: Res<GameSettings>, mut localization: ResMut<Localization>) {
+    if localization.language != settings.language {
+        localization.language = settings.language;
+    }
+}
+
+// Re-translate static labels after a language switch
+fn update_localized_texts(
+    localization: Res<Localization>,
+    mut query: Query<(&mut Text, &LocalizedText)>,
+) {
+    for (mut text, localized) in query.iter_mut() {
+        **text = localization.t(localized.0);
+    }
+}
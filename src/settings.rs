@@ -0,0 +1,272 @@
+use bevy::{
+    audio::{AudioSinkPlayback, GlobalVolume, Volume},
+    prelude::*,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, VideoModeSelection, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+use crate::combat::CombatMessageQueue;
+use crate::ui::StoryText;
+
+pub const SETTINGS_PATH: &str = "settings/settings.ron";
+
+pub fn plugin(app: &mut App) {
+    app
+        .init_resource::<GameSettings>()
+        .init_resource::<SettingsSaveError>()
+        .add_systems(Startup, load_settings_system)
+        .add_systems(Update, (
+            apply_settings_system,
+            apply_channel_volumes_system,
+            save_settings_system,
+        ).run_if(resource_changed::<GameSettings>))
+        .add_systems(Update, set_new_sound_volumes_system);
+}
+
+// User settings, saved to settings/settings.ron
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub master_volume: f32,
+    // Music and sound effects are scaled on top of the master volume (see AudioChannel)
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub text_speed: TextSpeed,
+    pub combat_speed: f32, // playback speed of the combat log, one of COMBAT_SPEEDS
+    pub window_mode: WindowModeSetting,
+    pub vsync: bool,
+    pub ui_scale: f32,
+    pub language: Language,
+}
+
+// Why the last save failed, shown in the settings menu
+#[derive(Resource, Default)]
+pub struct SettingsSaveError(pub Option<String>);
+
+// Every sound is spawned on a channel, e.g. (AudioPlayer::new(handle), AudioChannel::Sfx),
+// so the music and effect volumes apply to it
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum AudioChannel {
+    Music,
+    Sfx,
+}
+
+impl AudioChannel {
+    pub fn volume(&self, settings: &GameSettings) -> Volume {
+        match self {
+            AudioChannel::Music => Volume::Linear(settings.music_volume),
+            AudioChannel::Sfx => Volume::Linear(settings.sfx_volume),
+        }
+    }
+}
+
+pub const UI_SCALES: [f32; 4] = [0.75, 1.0, 1.25, 1.5];
+pub const COMBAT_SPEEDS: [f32; 3] = [1.0, 2.0, 4.0];
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.8,
+            sfx_volume: 0.8,
+            text_speed: TextSpeed::Normal,
+            combat_speed: 1.0,
+            window_mode: WindowModeSetting::Windowed,
+            vsync: true,
+            ui_scale: 1.0,
+            language: Language::default(),
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn load(path: &str) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => match ron::from_str::<GameSettings>(&content) {
+                Ok(loaded) => {
+                    info!("Loaded settings from {}", path);
+                    loaded
+                }
+                Err(err) => {
+                    warn!("Invalid settings in {}: {}", path, err);
+                    Self::default()
+                }
+            },
+            Err(_) => {
+                info!("No settings in {}, using defaults", path);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)?;
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TextSpeed {
+    Slow,
+    Normal,
+    Fast,
+    VeryFast,
+}
+
+impl TextSpeed {
+    pub const ALL: [TextSpeed; 4] = [TextSpeed::Slow, TextSpeed::Normal, TextSpeed::Fast, TextSpeed::VeryFast];
+
+    // Delay between two characters of the story typewriter
+    pub fn seconds_per_char(&self) -> f32 {
+        match self {
+            TextSpeed::Slow => 0.08,
+            TextSpeed::Normal => 0.05,
+            TextSpeed::Fast => 0.025,
+            TextSpeed::VeryFast => 0.01,
+        }
+    }
+
+    pub fn name_key(&self) -> &'static str {
+        match self {
+            TextSpeed::Slow => "settings.text_speed.slow",
+            TextSpeed::Normal => "settings.text_speed.normal",
+            TextSpeed::Fast => "settings.text_speed.fast",
+            TextSpeed::VeryFast => "settings.text_speed.very_fast",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WindowModeSetting {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub const ALL: [WindowModeSetting; 3] = [
+        WindowModeSetting::Windowed,
+        WindowModeSetting::Borderless,
+        WindowModeSetting::Fullscreen,
+    ];
+
+    pub fn window_mode(&self) -> WindowMode {
+        match self {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Borderless => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+            WindowModeSetting::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+
+    pub fn name_key(&self) -> &'static str {
+        match self {
+            WindowModeSetting::Windowed => "settings.window_mode.windowed",
+            WindowModeSetting::Borderless => "settings.window_mode.borderless",
+            WindowModeSetting::Fullscreen => "settings.window_mode.fullscreen",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    German,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::German, Language::English];
+
+    // File name of the string table in assets/locale
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::German => "de",
+            Language::English => "en",
+        }
+    }
+
+    // Always shown in the language itself
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::German => "Deutsch",
+            Language::English => "English",
+        }
+    }
+}
+
+// Next or previous entry of a list of options, wrapping around
+pub fn cycle<T: Copy + PartialEq>(options: &[T], current: T, forward: bool) -> T {
+    let index = options.iter().position(|option| *option == current).unwrap_or(0);
+    let next = if forward {
+        (index + 1) % options.len()
+    } else {
+        (index + options.len() - 1) % options.len()
+    };
+    options[next]
+}
+
+// System that loads the user's settings at startup
+fn load_settings_system(mut settings: ResMut<GameSettings>) {
+    *settings = GameSettings::load(SETTINGS_PATH);
+}
+
+// Push the settings to the window, audio, UI, typewriter and combat log whenever they change
+fn apply_settings_system(
+    settings: Res<GameSettings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut ui_scale: ResMut<UiScale>,
+    mut story_text: ResMut<StoryText>,
+    mut message_queue: ResMut<CombatMessageQueue>,
+) {
+    if let Ok(mut window) = window_query.single_mut() {
+        window.mode = settings.window_mode.window_mode();
+        window.present_mode = if settings.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+    }
+
+    global_volume.volume = Volume::Linear(settings.master_volume);
+    ui_scale.0 = settings.ui_scale;
+    story_text.seconds_per_char = settings.text_speed.seconds_per_char();
+    message_queue.speed = settings.combat_speed;
+}
+
+// Sounds start with the volume of their channel, the master volume comes from GlobalVolume
+fn set_new_sound_volumes_system(
+    settings: Res<GameSettings>,
+    mut sound_query: Query<(&AudioChannel, &mut PlaybackSettings), Added<AudioChannel>>,
+) {
+    for (channel, mut playback) in sound_query.iter_mut() {
+        playback.volume = channel.volume(&settings);
+    }
+}
+
+// Sounds that are already playing follow the channel volumes
+fn apply_channel_volumes_system(settings: Res<GameSettings>, mut sink_query: Query<(&AudioChannel, &mut AudioSink)>) {
+    for (channel, mut sink) in sink_query.iter_mut() {
+        sink.set_volume(channel.volume(&settings));
+    }
+}
+
+// Write the settings back whenever they change, wherever they were changed
+fn save_settings_system(settings: Res<GameSettings>, mut save_error: ResMut<SettingsSaveError>) {
+    // Just loaded at startup, nothing new to save
+    if settings.is_added() {
+        return;
+    }
+    match settings.save(SETTINGS_PATH) {
+        Ok(()) => save_error.0 = None,
+        Err(err) => {
+            warn!("Could not save settings to {}: {}", SETTINGS_PATH, err);
+            save_error.0 = Some(err.to_string());
+        }
+    }
+}
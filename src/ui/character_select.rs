@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use crate::game_state::GameState;
 use crate::input::CharacterSelection;
+use crate::localization::Localization;
+use crate::world::RunSetup;
 
 #[derive(Component)]
 pub struct CharSelectMarker;
@@ -11,14 +13,25 @@ pub struct CharSelectButton(pub usize);
 #[derive(Component)]
 pub struct CharSelectButtonText;
 
+#[derive(Component)]
+pub struct DifficultyText;
+
+// Button below the difficulty that turns permadeath on and off
+#[derive(Component)]
+pub struct PermadeathButton;
+
+#[derive(Component)]
+pub struct PermadeathText;
+
 pub fn setup_character_select(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut char_sel: ResMut<CharacterSelection>,
+    loc: Res<Localization>,
 ) {
     let font = asset_server.load("fonts/atlantisheadbold.ttf");
     char_sel.current = 0;
-    char_sel.max = 1;
+    char_sel.max = CharacterSelection::PERMADEATH;
 
     commands.spawn((
         Node {
@@ -35,7 +48,7 @@ pub fn setup_character_select(
     .with_children(|parent| {
         // Title
         parent.spawn((
-            Text::new("CHARACTER"),
+            Text::new(loc.t("character_select.title")),
             TextFont {
                 font: font.clone(),
                 font_size: 50.0,
@@ -58,7 +71,7 @@ pub fn setup_character_select(
             CharSelectButton(0),
         ))
         .with_child((
-            Text::new("Schwertkrieger"),
+            Text::new(loc.t("character.sword_fighter")),
             TextFont {
                 font: font.clone(),
                 font_size: 30.0,
@@ -82,22 +95,107 @@ pub fn setup_character_select(
             CharSelectButton(1),
         ))
         .with_child((
-            Text::new("Schildkrieger"),
+            Text::new(loc.t("character.shield_fighter")),
             TextFont {
-                font,
+                font: font.clone(),
                 font_size: 30.0,
                 ..default()
             },
             TextColor(Color::srgb(0.9, 0.9, 0.9)),
             CharSelectButtonText,
         ));
+
+        // Difficulty, changed with left/right, filled in by update_difficulty_text
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.7, 0.3)),
+            TextLayout::new_with_justify(Justify::Center),
+            Node {
+                margin: UiRect::top(Val::Px(20.0)),
+                ..default()
+            },
+            DifficultyText,
+        ));
+
+        // Permadeath toggle, filled in by update_permadeath_text
+        parent.spawn((
+            Button,
+            Node {
+                margin: UiRect::all(Val::Px(8.0)),
+                padding: UiRect::all(Val::Px(8.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            CharSelectButton(CharacterSelection::PERMADEATH),
+            PermadeathButton,
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont {
+                font,
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.4, 0.4)),
+            PermadeathText,
+        ));
     });
 }
 
+pub fn update_difficulty_text(
+    run_setup: Res<RunSetup>,
+    loc: Res<Localization>,
+    mut query: Query<(&mut Text, Ref<DifficultyText>)>,
+) {
+    for (mut text, difficulty_text) in query.iter_mut() {
+        if !run_setup.is_changed() && !loc.is_changed() && !difficulty_text.is_added() {
+            continue;
+        }
+        let difficulty = run_setup.difficulty;
+        **text = loc.t_with("character_select.difficulty", &[("difficulty", &loc.t(difficulty.name_key()))]);
+    }
+}
+
+pub fn update_permadeath_text(
+    run_setup: Res<RunSetup>,
+    char_sel: Res<CharacterSelection>,
+    loc: Res<Localization>,
+    mut query: Query<(&mut Text, Ref<PermadeathText>)>,
+) {
+    for (mut text, permadeath_text) in query.iter_mut() {
+        if !run_setup.is_changed() && !char_sel.is_changed() && !loc.is_changed() && !permadeath_text.is_added() {
+            continue;
+        }
+        let state = if run_setup.permadeath { "character_select.permadeath_on" } else { "character_select.permadeath_off" };
+        let prefix = if char_sel.current == CharacterSelection::PERMADEATH { "> " } else { "" };
+        **text = format!("{}{}", prefix, loc.t_with("character_select.permadeath", &[("state", &loc.t(state))]));
+    }
+}
+
+// Clicking the toggle flips permadeath instead of starting the run
+pub fn update_permadeath_button(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PermadeathButton>)>,
+    mut char_sel: ResMut<CharacterSelection>,
+    mut run_setup: ResMut<RunSetup>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            char_sel.current = CharacterSelection::PERMADEATH;
+            run_setup.permadeath = !run_setup.permadeath;
+        }
+    }
+}
+
 pub fn update_character_select_buttons(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor, &CharSelectButton, &Children),
-        (Changed<Interaction>, With<Button>),
+        (Changed<Interaction>, With<Button>, Without<PermadeathButton>),
     >,
     mut text_query: Query<&mut Text, With<CharSelectButtonText>>,
     mut char_sel: ResMut<CharacterSelection>,
@@ -125,4 +223,31 @@ pub fn update_character_select_buttons(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// Highlight the class picked with keyboard or gamepad
+pub fn highlight_selected_character_button(
+    char_sel: Res<CharacterSelection>,
+    mut button_query: Query<(&CharSelectButton, &mut BackgroundColor, &Children)>,
+    mut text_query: Query<&mut Text, With<CharSelectButtonText>>,
+    new_buttons: Query<(), Added<CharSelectButton>>,
+) {
+    if !char_sel.is_changed() && new_buttons.is_empty() {
+        return;
+    }
+    
+    for (button, mut bg_color, children) in button_query.iter_mut() {
+        let selected = button.0 == char_sel.current;
+        *bg_color = if selected {
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.25))
+        } else {
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15))
+        };
+        // The permadeath toggle has its own text, see update_permadeath_text
+        let Ok(mut text) = text_query.get_mut(children[0]) else {
+            continue;
+        };
+        let label = text.0.trim_start_matches("> ").to_string();
+        text.0 = if selected { format!("> {}", label) } else { label };
+    }
+}
@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::character::*;
+use crate::combat::*;
+use crate::localization::Localization;
+use super::combat_ui::{CombatUIMarker, EnemyPanel, PlayerPanel};
+
+// Damage number or popup that floats up and fades out
+#[derive(Component)]
+pub struct FloatingText {
+    pub timer: Timer,
+    pub start_top: f32,
+    pub color: Color,
+}
+
+// Shakes the combat UI after a heavy hit
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    pub remaining: f32, // seconds left
+    pub intensity: f32, // max offset in px
+}
+
+// Attack results waiting for the combat log to catch up
+#[derive(Default)]
+pub struct PendingFeedback {
+    events: Vec<(Entity, AttackOutcome, bool)>,
+    waited_frame: bool,
+}
+
+const FLOAT_DURATION: f32 = 1.2;
+const FLOAT_DISTANCE: f32 = 60.0;
+
+// Spawn damage numbers and popups once the log shows the attack
+pub fn spawn_attack_feedback(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    loc: Res<Localization>,
+    mut attack_events: MessageReader<AttackResolvedEvent>,
+    message_queue: Res<CombatMessageQueue>,
+    mut shake: ResMut<ScreenShake>,
+    mut pending: Local<PendingFeedback>,
+    player_query: Query<(), With<Player>>,
+    player_panel: Query<Entity, With<PlayerPanel>>,
+    enemy_panel: Query<Entity, With<EnemyPanel>>,
+) {
+    for event in attack_events.read() {
+        pending.events.push((event.target, event.outcome, event.heavy));
+        pending.waited_frame = false;
+    }
+    
+    // Messages of the attack may only be queued next frame, so wait at least one
+    if pending.events.is_empty() || !message_queue.is_empty() || !pending.waited_frame {
+        pending.waited_frame = true;
+        return;
+    }
+    
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    let mut rng = rand::rng();
+    for (target, outcome, heavy) in pending.events.drain(..) {
+        let panel = if player_query.get(target).is_ok() {
+            player_panel.single()
+        } else {
+            enemy_panel.single()
+        };
+        let Ok(panel) = panel else {
+            continue;
+        };
+        
+        let (label, color, font_size) = match outcome {
+            AttackOutcome::Hit { damage } => (
+                format!("-{}", damage),
+                Color::srgb(1.0, 0.2, 0.2),
+                if heavy { 56.0 } else { 40.0 },
+            ),
+            AttackOutcome::Parried => (loc.t("feedback.parried"), Color::srgb(0.3, 0.6, 1.0), 36.0),
+            AttackOutcome::Missed => (loc.t("feedback.missed"), Color::srgb(0.7, 0.7, 0.7), 32.0),
+        };
+        
+        let start_top = rng.random_range(120.0..180.0);
+        commands.entity(panel).with_child((
+            Text::new(label),
+            TextFont {
+                font: font.clone(),
+                font_size,
+                ..default()
+            },
+            TextColor(color),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(start_top),
+                left: Val::Percent(rng.random_range(30.0..60.0)),
+                ..default()
+            },
+            FloatingText {
+                timer: Timer::from_seconds(FLOAT_DURATION, TimerMode::Once),
+                start_top,
+                color,
+            },
+        ));
+        
+        if heavy {
+            shake.remaining = 0.35;
+            shake.intensity = 10.0;
+        }
+    }
+}
+
+// Move floating texts up, fade them out and remove them when done
+pub fn animate_floating_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut FloatingText, &mut Node, &mut TextColor)>,
+) {
+    for (entity, mut floating, mut node, mut color) in query.iter_mut() {
+        floating.timer.tick(time.delta());
+        if floating.timer.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        
+        let progress = floating.timer.fraction();
+        node.top = Val::Px(floating.start_top - progress * FLOAT_DISTANCE);
+        color.0 = floating.color.with_alpha(1.0 - progress);
+    }
+}
+
+// Offset the combat UI randomly while a shake is running
+pub fn apply_screen_shake(
+    time: Res<Time>,
+    mut shake: ResMut<ScreenShake>,
+    mut root_query: Query<&mut Node, With<CombatUIMarker>>,
+) {
+    if shake.remaining <= 0.0 {
+        return;
+    }
+    shake.remaining -= time.delta_secs();
+    
+    let mut rng = rand::rng();
+    let (x, y) = if shake.remaining > 0.0 {
+        let strength = shake.intensity * (shake.remaining / 0.35).min(1.0);
+        (rng.random_range(-strength..=strength), rng.random_range(-strength..=strength))
+    } else {
+        (0.0, 0.0)
+    };
+    
+    for mut node in root_query.iter_mut() {
+        node.left = Val::Px(x);
+        node.top = Val::Px(y);
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::{asset::LoadState, prelude::*};
+use crate::character::*;
+use crate::combat::*;
+
+// Sprite of a combatant in the combat scene
+#[derive(Component)]
+pub struct CombatSprite {
+    pub owner: Entity,
+    pub sheet: SpriteSheet,
+    pub animation: SpriteAnimation,
+    pub frame: usize,
+    pub frame_timer: Timer,
+    pub effect_timer: Timer, // drives lunge/flash/fade of the current animation
+    pub home: Vec3,
+    pub using_fallback: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpriteAnimation {
+    Idle,
+    Attack,
+    Hit,
+    Death,
+}
+
+impl CombatSprite {
+    fn frames(&self) -> (usize, usize) {
+        match self.animation {
+            SpriteAnimation::Idle => self.sheet.idle,
+            SpriteAnimation::Attack => self.sheet.attack,
+            SpriteAnimation::Hit => self.sheet.hit,
+            SpriteAnimation::Death => self.sheet.death,
+        }
+    }
+
+    fn play(&mut self, animation: SpriteAnimation) {
+        // Dead stays dead
+        if self.animation == SpriteAnimation::Death {
+            return;
+        }
+        self.animation = animation;
+        self.frame = self.frames().0;
+        self.frame_timer.reset();
+        self.effect_timer.reset();
+    }
+}
+
+const SPRITE_SIZE: f32 = 80.0;
+const FRAME_TIME: f32 = 0.15;
+const EFFECT_TIME: f32 = 0.4;
+
+// Scene sits in the middle column, player left, enemies in a grid on the right
+fn slot_position(is_player: bool, enemy_slot: usize) -> Vec3 {
+    if is_player {
+        Vec3::new(-120.0, 40.0, 0.0)
+    } else {
+        let column = (enemy_slot % 2) as f32;
+        let row = (enemy_slot / 2) as f32;
+        Vec3::new(40.0 + column * 100.0, 160.0 - row * 100.0, 0.0)
+    }
+}
+
+// Spawn sprites for combatants that don't have one yet (also summons and reinforcements)
+pub fn spawn_combat_sprites(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    combatant_query: Query<(Entity, &SpriteSheet, Has<Player>), With<Combatant>>,
+    sprite_query: Query<&CombatSprite>,
+) {
+    let mut enemy_slot = sprite_query.iter().filter(|sprite| sprite.home.x > 0.0).count();
+    
+    for (owner, sheet, is_player) in combatant_query.iter() {
+        if sprite_query.iter().any(|sprite| sprite.owner == owner) {
+            continue;
+        }
+        
+        let home = slot_position(is_player, enemy_slot);
+        if !is_player {
+            enemy_slot += 1;
+        }
+        
+        // No texture configured, the rectangle is drawn right away
+        let using_fallback = sheet.path.is_empty();
+        let mut sprite = if using_fallback {
+            Sprite::from_color(sheet.fallback_color, Vec2::splat(SPRITE_SIZE))
+        } else {
+            let layout = layouts.add(TextureAtlasLayout::from_grid(
+                sheet.tile_size,
+                sheet.columns,
+                sheet.rows,
+                None,
+                None,
+            ));
+            Sprite::from_atlas_image(
+                asset_server.load(sheet.path.clone()),
+                TextureAtlas { layout, index: sheet.idle.0 },
+            )
+        };
+        sprite.custom_size = Some(Vec2::splat(SPRITE_SIZE));
+        // Enemies face the player
+        sprite.flip_x = !is_player;
+        
+        commands.spawn((
+            sprite,
+            Transform::from_translation(home),
+            CombatSprite {
+                owner,
+                sheet: sheet.clone(),
+                animation: SpriteAnimation::Idle,
+                frame: sheet.idle.0,
+                frame_timer: Timer::from_seconds(FRAME_TIME, TimerMode::Repeating),
+                effect_timer: Timer::from_seconds(EFFECT_TIME, TimerMode::Once),
+                home,
+                using_fallback,
+            },
+        ));
+    }
+}
+
+// Draw a colored rectangle instead of a texture that failed to load
+pub fn apply_sprite_fallbacks(
+    asset_server: Res<AssetServer>,
+    mut sprite_query: Query<(&mut Sprite, &mut CombatSprite)>,
+) {
+    for (mut sprite, mut combat_sprite) in sprite_query.iter_mut() {
+        if combat_sprite.using_fallback {
+            continue;
+        }
+        if matches!(asset_server.load_state(&sprite.image), LoadState::Failed(_)) {
+            let flip_x = sprite.flip_x;
+            *sprite = Sprite::from_color(combat_sprite.sheet.fallback_color, Vec2::splat(SPRITE_SIZE));
+            sprite.flip_x = flip_x;
+            combat_sprite.using_fallback = true;
+        }
+    }
+}
+
+// Start attack, hit and death animations from combat events
+pub fn trigger_sprite_animations(
+    mut attack_events: MessageReader<AttackResolvedEvent>,
+    health_query: Query<&Health>,
+    mut sprite_query: Query<&mut CombatSprite>,
+) {
+    for event in attack_events.read() {
+        for mut sprite in sprite_query.iter_mut() {
+            if sprite.owner == event.attacker {
+                sprite.play(SpriteAnimation::Attack);
+            } else if sprite.owner == event.target
+                && matches!(event.outcome, AttackOutcome::Hit { .. })
+            {
+                sprite.play(SpriteAnimation::Hit);
+            }
+        }
+    }
+    
+    for mut sprite in sprite_query.iter_mut() {
+        let dead = health_query.get(sprite.owner).is_ok_and(|health| !health.is_alive());
+        if dead && sprite.animation != SpriteAnimation::Death {
+            sprite.play(SpriteAnimation::Death);
+        }
+    }
+}
+
+// Advance frames and movement effects, remove sprites of despawned combatants
+pub fn animate_combat_sprites(
+    mut commands: Commands,
+    time: Res<Time>,
+    combatant_query: Query<(), With<Combatant>>,
+    mut sprite_query: Query<(Entity, &mut CombatSprite, &mut Sprite, &mut Transform)>,
+) {
+    for (entity, mut combat_sprite, mut sprite, mut transform) in sprite_query.iter_mut() {
+        if combatant_query.get(combat_sprite.owner).is_err() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        
+        // Frame animation (only with a texture)
+        combat_sprite.frame_timer.tick(time.delta());
+        if combat_sprite.frame_timer.just_finished() {
+            let (first, last) = combat_sprite.frames();
+            if combat_sprite.frame < last {
+                combat_sprite.frame += 1;
+            } else if combat_sprite.animation == SpriteAnimation::Idle {
+                combat_sprite.frame = first;
+            } else if combat_sprite.animation != SpriteAnimation::Death {
+                combat_sprite.play(SpriteAnimation::Idle);
+            }
+        }
+        let frame = combat_sprite.frame;
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            atlas.index = frame;
+        }
+        
+        // Movement effects, these also work for fallback rectangles
+        combat_sprite.effect_timer.tick(time.delta());
+        let progress = combat_sprite.effect_timer.fraction();
+        let pulse = (progress * std::f32::consts::PI).sin();
+        let home = combat_sprite.home;
+        let toward_center = if home.x < 0.0 { 1.0 } else { -1.0 };
+        let base_color = if combat_sprite.using_fallback {
+            combat_sprite.sheet.fallback_color
+        } else {
+            Color::WHITE
+        };
+        
+        match combat_sprite.animation {
+            SpriteAnimation::Idle => {
+                transform.translation = home;
+                sprite.color = base_color;
+            }
+            SpriteAnimation::Attack => {
+                transform.translation = home + Vec3::X * toward_center * 40.0 * pulse;
+            }
+            SpriteAnimation::Hit => {
+                transform.translation = home - Vec3::X * toward_center * 12.0 * pulse;
+                sprite.color = base_color.mix(&Color::srgb(1.0, 0.2, 0.2), pulse);
+            }
+            SpriteAnimation::Death => {
+                transform.translation = home - Vec3::Y * 20.0 * progress;
+                sprite.color = base_color.with_alpha(1.0 - 0.7 * progress);
+            }
+        }
+    }
+}
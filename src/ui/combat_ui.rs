@@ -1,7 +1,11 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 use crate::character::*;
 use crate::combat::*;
-use super::CombatLog;
+use crate::input::{ActionPrompts, ActionState, GameAction};
+use crate::localization::{Localization, LocalizedText};
+use crate::settings::{cycle, GameSettings, COMBAT_SPEEDS};
+use crate::world::BossBehavior;
+use super::{CombatLog, CombatLogEntry};
 
 #[derive(Component)]
 pub struct CombatUIMarker;
@@ -13,7 +17,34 @@ pub struct PlayerHPBar;
 pub struct PlayerHPText;
 
 #[derive(Component)]
-pub struct EnemyListText;
+pub struct EnemyCardList;
+
+// Clickable enemy card, index into the enemy list like `selected_enemy_index`
+#[derive(Component)]
+pub struct EnemyCardButton(pub usize);
+
+#[derive(Component)]
+pub struct CombatActionButtons;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum CombatActionButton {
+    Finte(u32),
+    Wuchtschlag(u32),
+    Confirm,
+}
+
+// Background colors of a combat button, restored when the hover ends
+#[derive(Component)]
+pub struct ButtonColors {
+    pub normal: Color,
+    pub hovered: Color,
+}
+
+#[derive(Component)]
+pub struct EnemyTooltip;
+
+#[derive(Component)]
+pub struct EnemyTooltipText;
 
 #[derive(Component)]
 pub struct SelectedEnemyInfo;
@@ -24,6 +55,15 @@ pub struct CombatInstructionText;
 #[derive(Component)]
 pub struct CombatLogDisplay;
 
+#[derive(Component)]
+pub struct TurnTimeline;
+
+#[derive(Component)]
+pub struct PlayerPanel;
+
+#[derive(Component)]
+pub struct EnemyPanel;
+
 #[derive(Resource, Default)]
 pub struct CombatUIState {
     pub selected_enemy_index: usize,
@@ -43,6 +83,7 @@ pub enum CombatInputPhase {
 pub fn setup_combat_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    loc: Res<Localization>,
 ) {
     let font = asset_server.load("fonts/atlantisheadbold.ttf");
     
@@ -53,7 +94,8 @@ pub fn setup_combat_ui(
             flex_direction: FlexDirection::Column,
             ..default()
         },
-        BackgroundColor(Color::srgb(0.02, 0.02, 0.05)),
+        // Transparent so the sprite scene shows through the middle column
+        BackgroundColor(Color::NONE),
         CombatUIMarker,
     ))
     .with_children(|parent| {
@@ -71,13 +113,31 @@ pub fn setup_combat_ui(
             BorderColor::all(Color::srgb(0.8, 0.0, 0.0)),
         ))
         .with_child((
-            Text::new("⚔️ KAMPF ⚔️"),
+            Text::new(loc.t("combat_ui.title")),
             TextFont {
                 font: font.clone(),
                 font_size: 32.0,
                 ..default()
             },
             TextColor(Color::srgb(1.0, 0.2, 0.2)),
+            LocalizedText("combat_ui.title"),
+        ));
+        
+        // Turn order timeline (filled by update_turn_timeline)
+        parent.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                min_height: Val::Px(70.0),
+                padding: UiRect::axes(Val::Px(15.0), Val::Px(8.0)),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                overflow: Overflow::clip_x(),
+                border: UiRect::bottom(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.04, 0.04, 0.07)),
+            BorderColor::all(Color::srgb(0.3, 0.3, 0.4)),
+            TurnTimeline,
         ));
         
         // Main content area (3 columns)
@@ -89,13 +149,13 @@ pub fn setup_combat_ui(
         })
         .with_children(|main| {
             // Left column: Player info
-            create_player_column(main, &font);
+            create_player_column(main, &font, &loc);
             
             // Middle column: Combat scene
-            create_combat_scene_column(main, &font);
+            create_combat_scene_column(main);
             
             // Right column: Enemy info
-            create_enemy_column(main, &font);
+            create_enemy_column(main, &font, &loc);
         });
         
         // Bottom: Combat log
@@ -112,7 +172,7 @@ pub fn setup_combat_ui(
             BorderColor::all(Color::srgb(0.0, 0.5, 0.0)),
         ))
         .with_child((
-            Text::new("=== KAMPF-LOG ===\n"),
+            Text::new(loc.t("combat_ui.log_title")),
             TextFont {
                 font: font.clone(),
                 font_size: 16.0,
@@ -121,10 +181,35 @@ pub fn setup_combat_ui(
             TextColor(Color::srgb(0.6, 1.0, 0.6)),
             CombatLogDisplay,
         ));
+        
+        // Enemy stats tooltip, follows the pointer while hovering an enemy card
+        parent.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                padding: UiRect::all(Val::Px(8.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+            BorderColor::all(Color::srgb(0.6, 0.6, 0.7)),
+            GlobalZIndex(10),
+            EnemyTooltip,
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont {
+                font: font.clone(),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            EnemyTooltipText,
+        ));
     });
 }
 
-fn create_player_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font>) {
+fn create_player_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font>, loc: &Localization) {
     parent.spawn((
         Node {
             width: Val::Percent(33.3),
@@ -136,17 +221,19 @@ fn create_player_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Fon
         },
         BackgroundColor(Color::srgb(0.05, 0.05, 0.1)),
         BorderColor::all(Color::srgb(0.3, 0.3, 0.4)),
+        PlayerPanel,
     ))
     .with_children(|col| {
         // Title
         col.spawn((
-            Text::new("[ SPIELER ]"),
+            Text::new(loc.t("combat_ui.player_title")),
             TextFont {
                 font: font.clone(),
                 font_size: 24.0,
                 ..default()
             },
             TextColor(Color::srgb(0.0, 1.0, 1.0)),
+            LocalizedText("combat_ui.player_title"),
             Node {
                 margin: UiRect::bottom(Val::Px(20.0)),
                 ..default()
@@ -214,7 +301,7 @@ fn create_player_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Fon
         
         // Combat instructions
         col.spawn((
-            Text::new("Wähle Aktion..."),
+            Text::new(loc.t("combat_ui.choose_action")),
             TextFont {
                 font: font.clone(),
                 font_size: 16.0,
@@ -223,37 +310,36 @@ fn create_player_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Fon
             TextColor(Color::srgb(1.0, 1.0, 0.5)),
             CombatInstructionText,
         ));
+        
+        // Clickable Finte/Wuchtschlag levels, filled by update_combat_action_buttons
+        col.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                margin: UiRect::top(Val::Px(20.0)),
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            CombatActionButtons,
+        ));
     });
 }
 
-fn create_combat_scene_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font>) {
+fn create_combat_scene_column(parent: &mut ChildSpawnerCommands<'_>) {
+    // Empty window onto the combat sprites (see combat_scene.rs)
     parent.spawn((
         Node {
             width: Val::Percent(33.3),
             height: Val::Percent(100.0),
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
             border: UiRect::right(Val::Px(2.0)),
             ..default()
         },
-        BackgroundColor(Color::srgb(0.03, 0.03, 0.06)),
+        BackgroundColor(Color::NONE),
         BorderColor::all(Color::srgb(0.3, 0.3, 0.4)),
-    ))
-    .with_children(|col| {
-        col.spawn((
-            Text::new("⚔️  ⚡  💥"),
-            TextFont {
-                font: font.clone(),
-                font_size: 64.0,
-                ..default()
-            },
-            TextColor(Color::srgb(1.0, 0.8, 0.0)),
-        ));
-    });
+    ));
 }
 
-fn create_enemy_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font>) {
+fn create_enemy_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font>, loc: &Localization) {
     parent.spawn((
         Node {
             width: Val::Percent(33.3),
@@ -263,17 +349,19 @@ fn create_enemy_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font
             ..default()
         },
         BackgroundColor(Color::srgb(0.1, 0.05, 0.05)),
+        EnemyPanel,
     ))
     .with_children(|col| {
         // Title
         col.spawn((
-            Text::new("[ GEGNER ]"),
+            Text::new(loc.t("combat_ui.enemy_title")),
             TextFont {
                 font: font.clone(),
                 font_size: 24.0,
                 ..default()
             },
             TextColor(Color::srgb(1.0, 0.3, 0.3)),
+            LocalizedText("combat_ui.enemy_title"),
             Node {
                 margin: UiRect::bottom(Val::Px(20.0)),
                 ..default()
@@ -282,7 +370,7 @@ fn create_enemy_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font
         
         // Selected enemy details
         col.spawn((
-            Text::new("Wähle einen Gegner..."),
+            Text::new(loc.t("combat_ui.choose_enemy")),
             TextFont {
                 font: font.clone(),
                 font_size: 18.0,
@@ -296,16 +384,15 @@ fn create_enemy_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font
             SelectedEnemyInfo,
         ));
         
-        // Enemy list
+        // Enemy cards, filled by update_enemy_cards
         col.spawn((
-            Text::new("Keine Gegner"),
-            TextFont {
-                font: font.clone(),
-                font_size: 16.0,
+            Node {
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
                 ..default()
             },
-            TextColor(Color::srgb(0.8, 0.8, 0.8)),
-            EnemyListText,
+            EnemyCardList,
         ));
     });
 }
@@ -313,13 +400,14 @@ fn create_enemy_column(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font
 // Update systems
 pub fn update_combat_ui(
     ui_state: Res<CombatUIState>,
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
     player_query: Query<(&Health, &SpecialAbilities), With<Player>>,
     enemy_query: Query<(Entity, &CharacterType, &Health), With<Enemy>>,
     mut hp_bar_query: Query<&mut Node, With<PlayerHPBar>>,
     mut hp_text_query: Query<&mut Text, With<PlayerHPText>>,
-    mut instruction_query: Query<&mut Text, (With<CombatInstructionText>, Without<PlayerHPText>, Without<EnemyListText>, Without<SelectedEnemyInfo>)>,
-    mut enemy_list_query: Query<&mut Text, (With<EnemyListText>, Without<CombatInstructionText>, Without<PlayerHPText>, Without<SelectedEnemyInfo>)>,
-    mut selected_enemy_query: Query<&mut Text, (With<SelectedEnemyInfo>, Without<CombatInstructionText>, Without<PlayerHPText>, Without<EnemyListText>)>,
+    mut instruction_query: Query<&mut Text, (With<CombatInstructionText>, Without<PlayerHPText>, Without<SelectedEnemyInfo>)>,
+    mut selected_enemy_query: Query<&mut Text, (With<SelectedEnemyInfo>, Without<CombatInstructionText>, Without<PlayerHPText>)>,
 ) {
     // Update player HP
     if let Ok((health, abilities)) = player_query.single() {
@@ -335,81 +423,755 @@ pub fn update_combat_ui(
         
         // Update instructions based on phase
         for mut text in instruction_query.iter_mut() {
+            let navigate = format!("{} {}", prompts.label(GameAction::Left), prompts.label(GameAction::Right));
             **text = match ui_state.input_phase {
-                CombatInputPhase::SelectingEnemy => {
-                    format!(
-                        "Wähle Gegner:\n← → oder 1-{}\n\nENTER: Weiter",
-                        enemy_query.iter().count()
-                    )
-                }
-                CombatInputPhase::SelectingFinte => {
-                    format!(
-                        "Finte Level:\n← → oder 0-{}\n\nAktuell: {}\n\nENTER: Weiter\nESC: Zurück",
-                        abilities.finte_level,
-                        ui_state.selected_finte
-                    )
-                }
-                CombatInputPhase::SelectingWuchtschlag => {
-                    format!(
-                        "Wuchtschlag Level:\n← → oder 0-{}\n\nAktuell: {}\n\nENTER: Angriff!\nESC: Zurück",
-                        abilities.wuchtschlag_level,
-                        ui_state.selected_wuchtschlag
-                    )
-                }
+                CombatInputPhase::SelectingEnemy => loc.t_with("combat_ui.select_enemy", &[
+                    ("navigate", &navigate),
+                    ("max", &enemy_query.iter().count()),
+                    ("confirm", &prompts.label(GameAction::Confirm)),
+                    ("flee", &prompts.label(GameAction::Flee)),
+                ]),
+                CombatInputPhase::SelectingFinte => loc.t_with("combat_ui.select_finte", &[
+                    ("navigate", &navigate),
+                    ("max", &abilities.finte_level),
+                    ("current", &ui_state.selected_finte),
+                    ("confirm", &prompts.label(GameAction::Confirm)),
+                    ("back", &prompts.label(GameAction::Back)),
+                ]),
+                CombatInputPhase::SelectingWuchtschlag => loc.t_with("combat_ui.select_wuchtschlag", &[
+                    ("navigate", &navigate),
+                    ("max", &abilities.wuchtschlag_level),
+                    ("current", &ui_state.selected_wuchtschlag),
+                    ("confirm", &prompts.label(GameAction::Confirm)),
+                    ("back", &prompts.label(GameAction::Back)),
+                ]),
             };
         }
     }
     
-    // Update enemy list
+    // Update selected enemy details
+    if let Some((_, char_type, health)) = enemy_query.iter().nth(ui_state.selected_enemy_index) {
+        for mut text in selected_enemy_query.iter_mut() {
+            **text = loc.t_with("combat_ui.current_target", &[
+                ("name", &char_type.0),
+                ("current", &health.current),
+                ("max", &health.max),
+            ]);
+        }
+    }
+}
+
+type EnemyCardData<'a> = (Entity, &'a CharacterType, &'a Health, &'a Armor, Option<&'a BossBehavior>);
+
+type ChangedEnemy = (With<Enemy>, Or<(Changed<Health>, Changed<Armor>, Changed<BossBehavior>)>);
+
+// Run condition: selection, turn, HP or status of an enemy changed, or the list is new
+pub fn enemy_cards_changed(
+    ui_state: Res<CombatUIState>,
+    battle_state: Res<BattleState>,
+    changed_enemies: Query<(), ChangedEnemy>,
+    new_list: Query<(), Added<EnemyCardList>>,
+) -> bool {
+    ui_state.is_changed() || battle_state.is_changed() || !changed_enemies.is_empty() || !new_list.is_empty()
+}
+
+// Rebuild the enemy cards (runs if enemy_cards_changed)
+pub fn update_enemy_cards(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ui_state: Res<CombatUIState>,
+    battle_state: Res<BattleState>,
+    loc: Res<Localization>,
+    enemy_query: Query<EnemyCardData, With<Enemy>>,
+    list_query: Query<Entity, With<EnemyCardList>>,
+) {
+    let Ok(list) = list_query.single() else {
+        return;
+    };
+    
+    let current_actor = battle_state.turn_queue.get(battle_state.current_turn_index).copied();
     let enemies: Vec<_> = enemy_query.iter().collect();
-    if !enemies.is_empty() {
-        // Update enemy list
-        for mut text in enemy_list_query.iter_mut() {
-            let mut list = String::from("Alle Gegner:\n\n");
-            for (i, (_, char_type, health)) in enemies.iter().enumerate() {
-                let marker = if i == ui_state.selected_enemy_index { "►" } else { " " };
-                list.push_str(&format!(
-                    "{} {}. {} ({} HP)\n",
-                    marker,
-                    i + 1,
-                    char_type.0,
-                    health.current
-                ));
+    
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    commands.entity(list).despawn_related::<Children>();
+    commands.entity(list).with_children(|col| {
+        if enemies.is_empty() {
+            col.spawn((
+                Text::new(loc.t("combat_ui.no_enemies")),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+            return;
+        }
+        
+        for (i, (entity, char_type, health, armor, boss)) in enemies.iter().enumerate() {
+            // Status icons
+            let mut status = format!("🛡 {}", armor.0);
+            if boss.is_some() {
+                status.push_str(&format!("  {}", loc.t("combat_ui.status_boss")));
+            }
+            if boss.is_some_and(|boss| boss.charging_special) {
+                status.push_str(&format!("  {}", loc.t("combat_ui.status_charging")));
+            }
+            if current_actor == Some(*entity) {
+                status.push_str(&format!("  {}", loc.t("combat_ui.status_acting")));
             }
-            **text = list;
+            
+            spawn_enemy_card(
+                col,
+                &font,
+                i,
+                format!("{}. {}", i + 1, char_type.0),
+                status,
+                health,
+                i == ui_state.selected_enemy_index,
+            );
         }
+    });
+}
+
+fn spawn_enemy_card(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: &Handle<Font>,
+    index: usize,
+    name: String,
+    status: String,
+    health: &Health,
+    selected: bool,
+) {
+    let alive = health.is_alive();
+    let percent = (health.current as f32 / health.max as f32) * 100.0;
+    let (text_color, background, border) = if !alive {
+        (Color::srgb(0.4, 0.4, 0.4), Color::srgb(0.06, 0.06, 0.06), Color::srgb(0.2, 0.2, 0.2))
+    } else if selected {
+        (Color::srgb(1.0, 1.0, 0.5), Color::srgb(0.25, 0.12, 0.05), Color::srgb(1.0, 0.85, 0.0))
+    } else {
+        (Color::srgb(1.0, 0.6, 0.6), Color::srgb(0.15, 0.05, 0.05), Color::srgb(0.5, 0.2, 0.2))
+    };
+    
+    parent.spawn((
+        Button,
+        Node {
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(8.0)),
+            border: UiRect::all(Val::Px(if selected && alive { 3.0 } else { 1.0 })),
+            ..default()
+        },
+        BackgroundColor(background),
+        BorderColor::all(border),
+        ButtonColors {
+            normal: background,
+            hovered: background.lighter(0.08),
+        },
+        EnemyCardButton(index),
+    ))
+    .with_children(|card| {
+        // Name, struck through when dead
+        card.spawn(Node {
+            margin: UiRect::bottom(Val::Px(4.0)),
+            ..default()
+        })
+        .with_children(|name_row| {
+            let label = if !alive {
+                format!("☠ {}", name)
+            } else if selected {
+                format!("► {}", name)
+            } else {
+                name
+            };
+            name_row.spawn((
+                Text::new(label),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(text_color),
+            ));
+            if !alive {
+                name_row.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        right: Val::Px(0.0),
+                        top: Val::Percent(50.0),
+                        height: Val::Px(2.0),
+                        ..default()
+                    },
+                    BackgroundColor(text_color),
+                ));
+            }
+        });
+        
+        // HP bar, same look as the player bar
+        card.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Px(14.0),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.2, 0.0, 0.0)),
+            BorderColor::all(Color::srgb(0.6, 0.2, 0.2)),
+        ))
+        .with_child((
+            Node {
+                width: Val::Percent(percent),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(if alive { Color::srgb(0.2, 0.8, 0.2) } else { Color::srgb(0.3, 0.3, 0.3) }),
+        ));
+        
+        card.spawn((
+            Text::new(format!("{} / {} HP   {}", health.current, health.max, status)),
+            TextFont {
+                font: font.clone(),
+                font_size: 13.0,
+                ..default()
+            },
+            TextColor(text_color),
+            Node {
+                margin: UiRect::top(Val::Px(4.0)),
+                ..default()
+            },
+        ));
+    });
+}
+
+// Run condition: level selection or the player's abilities changed, or the buttons are new
+pub fn combat_action_buttons_changed(
+    ui_state: Res<CombatUIState>,
+    changed_abilities: Query<(), (With<Player>, Changed<SpecialAbilities>)>,
+    new_container: Query<(), Added<CombatActionButtons>>,
+) -> bool {
+    ui_state.is_changed() || !changed_abilities.is_empty() || !new_container.is_empty()
+}
+
+// Rebuild the level buttons (runs if combat_action_buttons_changed)
+pub fn update_combat_action_buttons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ui_state: Res<CombatUIState>,
+    loc: Res<Localization>,
+    player_query: Query<&SpecialAbilities, With<Player>>,
+    container_query: Query<Entity, With<CombatActionButtons>>,
+) {
+    let (Ok(container), Ok(abilities)) = (container_query.single(), player_query.single()) else {
+        return;
+    };
+    
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    commands.entity(container).despawn_related::<Children>();
+    commands.entity(container).with_children(|col| {
+        spawn_level_button_row(
+            col,
+            &font,
+            &loc.t("combat_ui.finte"),
+            abilities.finte_level,
+            ui_state.selected_finte,
+            CombatActionButton::Finte,
+        );
+        spawn_level_button_row(
+            col,
+            &font,
+            &loc.t("combat_ui.wuchtschlag"),
+            abilities.wuchtschlag_level,
+            ui_state.selected_wuchtschlag,
+            CombatActionButton::Wuchtschlag,
+        );
+        spawn_combat_button(
+            col,
+            &font,
+            loc.t("combat_ui.attack_button"),
+            CombatActionButton::Confirm,
+            Color::srgb(0.4, 0.1, 0.1),
+        );
+    });
+}
+
+fn spawn_level_button_row(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: &Handle<Font>,
+    label: &str,
+    max_level: u32,
+    selected: u32,
+    action: fn(u32) -> CombatActionButton,
+) {
+    parent.spawn(Node {
+        flex_direction: FlexDirection::Row,
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(6.0),
+        ..default()
+    })
+    .with_children(|row| {
+        row.spawn((
+            Text::new(format!("{}:", label)),
+            TextFont {
+                font: font.clone(),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+        ));
         
-        // Update selected enemy details
-        if let Some((_, char_type, health)) = enemies.get(ui_state.selected_enemy_index) {
-            for mut text in selected_enemy_query.iter_mut() {
-                **text = format!(
-                    "Aktuelles Ziel:\n\n{}\n\nHP: {} / {}",
-                    char_type.0,
-                    health.current,
-                    health.max
-                );
+        for level in 0..=max_level {
+            let background = if level == selected {
+                Color::srgb(0.5, 0.45, 0.0)
+            } else {
+                Color::srgb(0.15, 0.15, 0.15)
+            };
+            spawn_combat_button(row, font, level.to_string(), action(level), background);
+        }
+    });
+}
+
+fn spawn_combat_button(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: &Handle<Font>,
+    label: String,
+    action: CombatActionButton,
+    background: Color,
+) {
+    parent.spawn((
+        Button,
+        Node {
+            min_width: Val::Px(32.0),
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(background),
+        ButtonColors {
+            normal: background,
+            hovered: background.lighter(0.1),
+        },
+        action,
+    ))
+    .with_child((
+        Text::new(label),
+        TextFont {
+            font: font.clone(),
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+    ));
+}
+
+// Hover color for all combat buttons
+pub fn update_combat_button_colors(
+    mut interaction_query: Query<
+        (&Interaction, &ButtonColors, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, colors, mut color) in interaction_query.iter_mut() {
+        *color = match *interaction {
+            Interaction::Hovered | Interaction::Pressed => BackgroundColor(colors.hovered),
+            Interaction::None => BackgroundColor(colors.normal),
+        };
+    }
+}
+
+// Mouse and touch input, feeds the same state as the keyboard
+pub fn handle_combat_pointer_input(
+    mut commands: Commands,
+    mut ui_state: ResMut<CombatUIState>,
+    battle_state: Res<BattleState>,
+    player_query: Query<Entity, With<Player>>,
+    enemy_query: Query<Entity, With<Enemy>>,
+    card_query: Query<(&Interaction, &EnemyCardButton), Changed<Interaction>>,
+    action_query: Query<(&Interaction, &CombatActionButton), Changed<Interaction>>,
+) {
+    if !battle_state.combat_active || !battle_state.waiting_for_player_input {
+        return;
+    }
+    
+    let Ok(player_entity) = player_query.single() else {
+        return;
+    };
+    
+    for (interaction, card) in card_query.iter() {
+        if *interaction == Interaction::Pressed {
+            ui_state.selected_enemy_index = card.0;
+        }
+    }
+    
+    for (interaction, action) in action_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *action {
+            CombatActionButton::Finte(level) => {
+                ui_state.selected_finte = level;
+                ui_state.input_phase = CombatInputPhase::SelectingFinte;
+            }
+            CombatActionButton::Wuchtschlag(level) => {
+                ui_state.selected_wuchtschlag = level;
+                ui_state.input_phase = CombatInputPhase::SelectingWuchtschlag;
+            }
+            CombatActionButton::Confirm => {
+                queue_player_attack(&mut commands, &mut ui_state, player_entity, &enemy_query);
             }
         }
     }
 }
 
-pub fn update_combat_log_display(
-    combat_log: Res<CombatLog>,
-    mut query: Query<&mut Text, With<CombatLogDisplay>>,
+type EnemyTooltipData<'a> = (
+    &'a CharacterType,
+    &'a Health,
+    &'a Armor,
+    &'a Attack,
+    &'a Defense,
+    &'a Damage,
+    &'a DiceRoll,
+    &'a Initiative,
+);
+
+// Show the stats of the hovered enemy next to the pointer
+pub fn update_enemy_tooltip(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    touches: Res<Touches>,
+    ui_scale: Res<UiScale>,
+    loc: Res<Localization>,
+    card_query: Query<(&Interaction, &EnemyCardButton)>,
+    enemy_query: Query<EnemyTooltipData, With<Enemy>>,
+    mut tooltip_query: Query<&mut Node, With<EnemyTooltip>>,
+    mut text_query: Query<&mut Text, With<EnemyTooltipText>>,
+) {
+    let Ok(mut tooltip) = tooltip_query.single_mut() else {
+        return;
+    };
+    
+    let hovered = card_query.iter()
+        .find(|(interaction, _)| **interaction != Interaction::None)
+        .and_then(|(_, card)| enemy_query.iter().nth(card.0));
+    let pointer = window_query.single().ok()
+        .and_then(|window| window.cursor_position())
+        .or_else(|| touches.iter().next().map(|touch| touch.position()))
+        .map(|position| position / ui_scale.0); // UI positions are in scaled pixels
+    
+    let (Some((char_type, health, armor, attack, defense, damage, dice, initiative)), Some(pointer)) = (hovered, pointer) else {
+        tooltip.display = Display::None;
+        return;
+    };
+    
+    tooltip.display = Display::Flex;
+    // Open to the left, the enemy column is on the right edge
+    tooltip.left = Val::Px((pointer.x - 220.0).max(0.0));
+    tooltip.top = Val::Px(pointer.y + 16.0);
+    
+    for mut text in text_query.iter_mut() {
+        **text = loc.t_with("combat_ui.tooltip", &[
+            ("name", &char_type.0),
+            ("current", &health.current),
+            ("max", &health.max),
+            ("attack", &attack.0),
+            ("defense", &defense.0),
+            ("armor", &armor.0),
+            ("dice", &dice.0),
+            ("damage", &damage.0),
+            ("initiative", &initiative.base),
+        ]);
+    }
+}
+
+type ChangedCombatant = (With<Combatant>, Or<(Changed<Initiative>, Changed<Health>)>);
+
+// Run condition: turn order, initiative or HP of a combatant changed, or the timeline is new
+pub fn turn_timeline_changed(
+    battle_state: Res<BattleState>,
+    changed_combatants: Query<(), ChangedCombatant>,
+    new_timeline: Query<(), Added<TurnTimeline>>,
+) -> bool {
+    battle_state.is_changed() || !changed_combatants.is_empty() || !new_timeline.is_empty()
+}
+
+// Rebuild the turn order timeline (runs if turn_timeline_changed)
+pub fn update_turn_timeline(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    battle_state: Res<BattleState>,
+    rules: Res<CombatRules>,
+    loc: Res<Localization>,
+    combatant_query: Query<(&CharacterType, &Initiative, &Health, Has<Player>)>,
+    timeline_query: Query<Entity, With<TurnTimeline>>,
 ) {
-    for mut text in query.iter_mut() {
-        if !combat_log.messages.is_empty() {
-            **text = format!(
-                "=== KAMPF-LOG ===\n{}",
-                combat_log.messages.join("\n")
+    let Ok(timeline) = timeline_query.single() else {
+        return;
+    };
+    
+    // Current round as it is queued
+    let current_round: Vec<_> = battle_state.turn_queue
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &entity)| {
+            combatant_query.get(entity).ok().map(|combatant| (i, entity, combatant))
+        })
+        .collect();
+    
+    // Preview of the next round: living combatants, with joins and leaves applied,
+    // sorted like the real rebuild (base initiative and tiebreaker on equal rolls)
+    let next_round_entries = battle_state.turn_queue
+        .iter()
+        .chain(battle_state.pending_joins.iter())
+        .filter(|&&entity| !battle_state.is_leaving(entity))
+        .filter_map(|&entity| {
+            let (_, initiative, health, is_player) = combatant_query.get(entity).ok()?;
+            health.is_alive().then_some(TurnOrderEntry {
+                entity,
+                initiative: initiative.randomized,
+                base: initiative.base,
+                is_player,
+            })
+        })
+        .collect();
+    let next_round = sort_turn_order(next_round_entries, rules.tiebreaker);
+    
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    commands.entity(timeline).despawn_related::<Children>();
+    commands.entity(timeline).with_children(|row| {
+        spawn_timeline_label(row, &font, loc.t_with("combat_ui.round", &[("round", &battle_state.current_round)]));
+        
+        for (i, _, (char_type, initiative, health, is_player)) in &current_round {
+            let is_current = *i == battle_state.current_turn_index;
+            let (text_color, background, border) = if !health.is_alive() {
+                (Color::srgb(0.4, 0.4, 0.4), Color::srgb(0.06, 0.06, 0.06), Color::srgb(0.2, 0.2, 0.2))
+            } else if is_current {
+                (Color::srgb(1.0, 1.0, 0.5), Color::srgb(0.25, 0.22, 0.0), Color::srgb(1.0, 0.85, 0.0))
+            } else if *is_player {
+                (Color::srgb(0.0, 1.0, 1.0), Color::srgb(0.05, 0.1, 0.15), Color::srgb(0.2, 0.4, 0.5))
+            } else {
+                (Color::srgb(1.0, 0.5, 0.5), Color::srgb(0.15, 0.05, 0.05), Color::srgb(0.5, 0.2, 0.2))
+            };
+            
+            spawn_timeline_card(
+                row,
+                &font,
+                format!(
+                    "{}\nINI {}  HP {}/{}",
+                    char_type.0, initiative.randomized, health.current, health.max
+                ),
+                text_color,
+                background,
+                border,
             );
         }
+        
+        // Next round preview (initiative unknown if it gets re-rolled)
+        let preview_label = if rules.reroll_initiative_each_round {
+            "combat_ui.next_round_reroll"
+        } else {
+            "combat_ui.next_round"
+        };
+        spawn_timeline_label(row, &font, loc.t(preview_label));
+        
+        let preview = next_round.iter()
+            .filter_map(|&entity| combatant_query.get(entity).ok())
+            .map(|(char_type, _, _, _)| char_type.0.as_str())
+            .collect::<Vec<_>>()
+            .join(" → ");
+        spawn_timeline_label(row, &font, preview);
+    });
+}
+
+fn spawn_timeline_label(parent: &mut ChildSpawnerCommands<'_>, font: &Handle<Font>, label: String) {
+    parent.spawn((
+        Text::new(label),
+        TextFont {
+            font: font.clone(),
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.7, 0.7, 0.8)),
+        Node {
+            margin: UiRect::horizontal(Val::Px(8.0)),
+            ..default()
+        },
+    ));
+}
+
+fn spawn_timeline_card(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: &Handle<Font>,
+    label: String,
+    text_color: Color,
+    background: Color,
+    border: Color,
+) {
+    parent.spawn((
+        Node {
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+            margin: UiRect::right(Val::Px(6.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(background),
+        BorderColor::all(border),
+    ))
+    .with_child((
+        Text::new(label),
+        TextFont {
+            font: font.clone(),
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(text_color),
+    ));
+}
+
+// Queue incoming combat messages and reveal them one by one after their delay
+pub fn play_combat_messages(
+    time: Res<Time>,
+    actions: Res<ActionState>,
+    battle_state: Res<BattleState>,
+    mut message_events: MessageReader<CombatMessageEvent>,
+    mut queue: ResMut<CombatMessageQueue>,
+    mut combat_log: ResMut<CombatLog>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for event in message_events.read() {
+        queue.pending.push_back(PendingCombatMessage {
+            message: event.message.clone(),
+            message_type: event.message_type,
+            delay_ms: event.delay_ms,
+            round: battle_state.current_round,
+            actor: event.actor.clone(),
+            target: event.target.clone(),
+        });
+    }
+    
+    // Change playback speed (kept as setting) or skip to the end
+    if actions.just_pressed(GameAction::CycleSpeed) {
+        settings.combat_speed = cycle(&COMBAT_SPEEDS, settings.combat_speed, true);
+        queue.speed = settings.combat_speed;
+    }
+    let fast_forward = actions.just_pressed(GameAction::FastForward);
+    
+    if queue.is_empty() {
+        queue.elapsed_ms = 0.0;
+        return;
+    }
+    queue.elapsed_ms += time.delta_secs() * 1000.0 * queue.speed;
+    
+    while let Some(next) = queue.pending.front() {
+        let delay = next.delay_ms as f32;
+        if !fast_forward && queue.elapsed_ms < delay {
+            break;
+        }
+        queue.elapsed_ms = (queue.elapsed_ms - delay).max(0.0);
+        
+        let Some(next) = queue.pending.pop_front() else {
+            break;
+        };
+        combat_log.push(CombatLogEntry {
+            message: next.message,
+            message_type: next.message_type,
+            round: next.round,
+            actor: next.actor,
+            target: next.target,
+        });
+    }
+}
+
+// Start every fight with an empty log
+pub fn reset_combat_log(mut combat_log: ResMut<CombatLog>) {
+    combat_log.clear();
+}
+
+// Scroll through the full log
+pub fn scroll_combat_log(
+    actions: Res<ActionState>,
+    mut combat_log: ResMut<CombatLog>,
+) {
+    if actions.just_pressed(GameAction::ScrollUp) {
+        combat_log.scroll_up();
+    }
+    if actions.just_pressed(GameAction::ScrollDown) {
+        combat_log.scroll_down();
+    }
+}
+
+// Icon and color for each kind of log message
+pub fn message_style(message_type: MessageType) -> (&'static str, Color) {
+    match message_type {
+        MessageType::RoundStart => ("◆", Color::srgb(0.6, 0.6, 0.9)),
+        MessageType::PlayerAction => ("►", Color::srgb(0.0, 1.0, 1.0)),
+        MessageType::EnemyAction => ("◄", Color::srgb(1.0, 0.6, 0.2)),
+        MessageType::Upgrade => ("★", Color::srgb(1.0, 0.85, 0.0)),
+        MessageType::SpecialMove => ("✦", Color::srgb(0.9, 0.4, 1.0)),
+        MessageType::Damage => ("✖", Color::srgb(1.0, 0.25, 0.25)),
+        MessageType::Defense => ("◈", Color::srgb(0.3, 0.6, 1.0)),
+        MessageType::CombatStart => ("⚔", Color::srgb(1.0, 1.0, 1.0)),
+        MessageType::CombatEnd => ("⚑", Color::srgb(0.6, 1.0, 0.6)),
+        MessageType::Flee => ("➜", Color::srgb(0.8, 0.8, 0.5)),
+    }
+}
+
+// Replace the log lines under a Text with one colored span per entry
+pub fn spawn_log_spans(
+    commands: &mut Commands,
+    text_entity: Entity,
+    font: &TextFont,
+    entries: &[CombatLogEntry],
+) {
+    commands.entity(text_entity).despawn_related::<Children>();
+    commands.entity(text_entity).with_children(|text| {
+        for entry in entries {
+            let (icon, color) = message_style(entry.message_type);
+            let round = if entry.round > 0 {
+                format!("[R{}] ", entry.round)
+            } else {
+                String::new()
+            };
+            text.spawn((
+                TextSpan::new(format!("\n{} {}{}", icon, round, entry.message)),
+                font.clone(),
+                TextColor(color),
+            ));
+        }
+    });
+}
+
+pub fn update_combat_log_display(
+    mut commands: Commands,
+    combat_log: Res<CombatLog>,
+    queue: Res<CombatMessageQueue>,
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
+    mut query: Query<(Entity, &mut Text, &TextFont), With<CombatLogDisplay>>,
+) {
+    if !combat_log.is_changed() && !queue.is_changed() && !prompts.is_changed() && !loc.is_changed() {
+        return;
+    }
+    
+    for (entity, mut text, font) in query.iter_mut() {
+        let scrolled = if combat_log.scroll_offset > 0 {
+            format!(" [-{}]", combat_log.scroll_offset)
+        } else {
+            String::new()
+        };
+        **text = loc.t_with("combat_ui.log_header", &[
+            ("scrolled", &scrolled),
+            ("fast_forward", &prompts.label(GameAction::FastForward)),
+            ("cycle_speed", &prompts.label(GameAction::CycleSpeed)),
+            ("speed", &queue.speed),
+            ("scroll", &prompts.pair(GameAction::ScrollUp, GameAction::ScrollDown)),
+        ]);
+        spawn_log_spans(&mut commands, entity, font, combat_log.visible_entries());
     }
 }
 
 // Input handling
 pub fn handle_combat_ui_input(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     mut ui_state: ResMut<CombatUIState>,
     mut commands: Commands,
     player_query: Query<(Entity, &SpecialAbilities), With<Player>>,
@@ -430,80 +1192,80 @@ pub fn handle_combat_ui_input(
         return;
     };
     
+    let left = actions.just_pressed(GameAction::Left);
+    let right = actions.just_pressed(GameAction::Right);
+    let level_down = left || actions.just_pressed(GameAction::LevelDown);
+    let level_up = right || actions.just_pressed(GameAction::LevelUp);
+    let number = actions.selected_number().map(u32::from);
+    
     match ui_state.input_phase {
         CombatInputPhase::SelectingEnemy => {
-            if keyboard.just_pressed(KeyCode::ArrowLeft) && ui_state.selected_enemy_index > 0 {
+            if left && ui_state.selected_enemy_index > 0 {
                 ui_state.selected_enemy_index -= 1;
-            } else if keyboard.just_pressed(KeyCode::ArrowRight) && ui_state.selected_enemy_index < enemy_count - 1 {
+            } else if right && ui_state.selected_enemy_index < enemy_count - 1 {
                 ui_state.selected_enemy_index += 1;
-            } else if keyboard.just_pressed(KeyCode::Digit1) && enemy_count >= 1 {
-                ui_state.selected_enemy_index = 0;
-            } else if keyboard.just_pressed(KeyCode::Digit2) && enemy_count >= 2 {
-                ui_state.selected_enemy_index = 1;
-            } else if keyboard.just_pressed(KeyCode::Digit3) && enemy_count >= 3 {
-                ui_state.selected_enemy_index = 2;
-            } else if keyboard.just_pressed(KeyCode::Digit4) && enemy_count >= 4 {
-                ui_state.selected_enemy_index = 3;
-            } else if keyboard.just_pressed(KeyCode::Digit5) && enemy_count >= 5 {
-                ui_state.selected_enemy_index = 4;
-            } else if keyboard.just_pressed(KeyCode::Enter) {
+            } else if let Some(n) = number.filter(|&n| n >= 1 && n as usize <= enemy_count) {
+                ui_state.selected_enemy_index = n as usize - 1;
+            } else if actions.just_pressed(GameAction::Confirm) {
                 ui_state.input_phase = CombatInputPhase::SelectingFinte;
                 ui_state.selected_finte = 0;
+            } else if actions.just_pressed(GameAction::Flee) {
+                // Try to flee instead of attacking
+                commands.entity(player_entity).insert(FleeAttempt);
             }
         }
         
         CombatInputPhase::SelectingFinte => {
-            if keyboard.just_pressed(KeyCode::ArrowLeft) && ui_state.selected_finte > 0 {
+            if level_down && ui_state.selected_finte > 0 {
                 ui_state.selected_finte -= 1;
-            } else if keyboard.just_pressed(KeyCode::ArrowRight) && ui_state.selected_finte < abilities.finte_level {
+            } else if level_up && ui_state.selected_finte < abilities.finte_level {
                 ui_state.selected_finte += 1;
-            } else if keyboard.just_pressed(KeyCode::Digit0) {
-                ui_state.selected_finte = 0;
-            } else if keyboard.just_pressed(KeyCode::Digit1) && abilities.finte_level >= 1 {
-                ui_state.selected_finte = 1;
-            } else if keyboard.just_pressed(KeyCode::Digit2) && abilities.finte_level >= 2 {
-                ui_state.selected_finte = 2;
-            } else if keyboard.just_pressed(KeyCode::Digit3) && abilities.finte_level >= 3 {
-                ui_state.selected_finte = 3;
-            } else if keyboard.just_pressed(KeyCode::Enter) {
+            } else if let Some(n) = number.filter(|&n| n <= abilities.finte_level) {
+                ui_state.selected_finte = n;
+            } else if actions.just_pressed(GameAction::Confirm) {
                 ui_state.input_phase = CombatInputPhase::SelectingWuchtschlag;
                 ui_state.selected_wuchtschlag = 0;
-            } else if keyboard.just_pressed(KeyCode::Escape) {
+            } else if actions.just_pressed(GameAction::Back) {
                 ui_state.input_phase = CombatInputPhase::SelectingEnemy;
             }
         }
         
         CombatInputPhase::SelectingWuchtschlag => {
-            if keyboard.just_pressed(KeyCode::ArrowLeft) && ui_state.selected_wuchtschlag > 0 {
+            if level_down && ui_state.selected_wuchtschlag > 0 {
                 ui_state.selected_wuchtschlag -= 1;
-            } else if keyboard.just_pressed(KeyCode::ArrowRight) && ui_state.selected_wuchtschlag < abilities.wuchtschlag_level {
+            } else if level_up && ui_state.selected_wuchtschlag < abilities.wuchtschlag_level {
                 ui_state.selected_wuchtschlag += 1;
-            } else if keyboard.just_pressed(KeyCode::Digit0) {
-                ui_state.selected_wuchtschlag = 0;
-            } else if keyboard.just_pressed(KeyCode::Digit1) && abilities.wuchtschlag_level >= 1 {
-                ui_state.selected_wuchtschlag = 1;
-            } else if keyboard.just_pressed(KeyCode::Digit2) && abilities.wuchtschlag_level >= 2 {
-                ui_state.selected_wuchtschlag = 2;
-            } else if keyboard.just_pressed(KeyCode::Digit3) && abilities.wuchtschlag_level >= 3 {
-                ui_state.selected_wuchtschlag = 3;
-            } else if keyboard.just_pressed(KeyCode::Enter) {
+            } else if let Some(n) = number.filter(|&n| n <= abilities.wuchtschlag_level) {
+                ui_state.selected_wuchtschlag = n;
+            } else if actions.just_pressed(GameAction::Confirm) {
                 // Execute attack!
-                let enemies: Vec<Entity> = enemy_query.iter().collect();
-                if let Some(&target) = enemies.get(ui_state.selected_enemy_index) {
-                    commands.entity(player_entity).insert(QueuedAction {
-                        target: Some(target),
-                        finte_level: ui_state.selected_finte,
-                        wuchtschlag_level: ui_state.selected_wuchtschlag,
-                    });
-                }
-                
-                // Reset to selecting enemy
-                ui_state.input_phase = CombatInputPhase::SelectingEnemy;
-                ui_state.selected_finte = 0;
-                ui_state.selected_wuchtschlag = 0;
-            } else if keyboard.just_pressed(KeyCode::Escape) {
+                queue_player_attack(&mut commands, &mut ui_state, player_entity, &enemy_query);
+            } else if actions.just_pressed(GameAction::Back) {
                 ui_state.input_phase = CombatInputPhase::SelectingFinte;
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// Queue the selected attack for the player and reset the selection
+fn queue_player_attack(
+    commands: &mut Commands,
+    ui_state: &mut CombatUIState,
+    player_entity: Entity,
+    enemy_query: &Query<Entity, With<Enemy>>,
+) {
+    if let Some(target) = enemy_query.iter().nth(ui_state.selected_enemy_index) {
+        commands.entity(player_entity).insert(QueuedAction {
+            target: Some(target),
+            finte_level: ui_state.selected_finte,
+            wuchtschlag_level: ui_state.selected_wuchtschlag,
+            bonus_damage: 0,
+            bonus_dice: 0,
+        });
+    }
+    
+    // Reset to selecting enemy
+    ui_state.input_phase = CombatInputPhase::SelectingEnemy;
+    ui_state.selected_finte = 0;
+    ui_state.selected_wuchtschlag = 0;
+}
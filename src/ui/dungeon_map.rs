@@ -0,0 +1,263 @@
+use bevy::prelude::*;
+use crate::input::{ActionPrompts, GameAction, ShowMap};
+use crate::localization::{Localization, LocalizedText};
+use crate::world::{BossScripts, Room, WorldState};
+use super::GameplayHudMarker;
+
+// Room boxes are laid out in columns by depth, one row per branch
+const ROOM_WIDTH: f32 = 150.0;
+const ROOM_HEIGHT: f32 = 56.0;
+const COLUMN_WIDTH: f32 = 190.0;
+const ROW_HEIGHT: f32 = 100.0;
+
+#[derive(Component)]
+pub struct DungeonMapOverlay;
+
+// Holds rooms and connections, rebuilt by update_dungeon_map
+#[derive(Component)]
+pub struct DungeonMapPanel;
+
+#[derive(Component)]
+pub struct DungeonMapHint;
+
+pub fn setup_dungeon_map(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    show_map: Res<ShowMap>,
+    loc: Res<Localization>,
+) {
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            display: if show_map.0 { Display::Flex } else { Display::None },
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.02, 0.02, 0.04, 0.92)),
+        GlobalZIndex(20),
+        GameplayHudMarker,
+        DungeonMapOverlay,
+    ))
+    .with_children(|overlay| {
+        overlay.spawn((
+            Text::new(loc.t("map.title")),
+            TextFont {
+                font: font.clone(),
+                font_size: 32.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.85, 0.6)),
+            LocalizedText("map.title"),
+        ));
+        overlay.spawn((Node::default(), DungeonMapPanel));
+        overlay.spawn((
+            Text::new(loc.t("map.legend")),
+            TextFont {
+                font: font.clone(),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.7, 0.8)),
+            LocalizedText("map.legend"),
+        ));
+        overlay.spawn((
+            Text::new(""),
+            TextFont {
+                font,
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.6, 0.6, 0.7)),
+            DungeonMapHint,
+        ));
+    });
+}
+
+// Show or hide the map when ShowMap is toggled
+pub fn toggle_dungeon_map(
+    show_map: Res<ShowMap>,
+    mut query: Query<&mut Node, With<DungeonMapOverlay>>,
+) {
+    for mut node in query.iter_mut() {
+        node.display = if show_map.0 { Display::Flex } else { Display::None };
+    }
+}
+
+// Draw visited, current and known rooms with the doors between them
+pub fn update_dungeon_map(
+    mut commands: Commands,
+    world: Res<WorldState>,
+    boss_scripts: Res<BossScripts>,
+    loc: Res<Localization>,
+    asset_server: Res<AssetServer>,
+    mut panel_query: Query<(Entity, &mut Node), With<DungeonMapPanel>>,
+    new_panel: Query<(), Added<DungeonMapPanel>>,
+) {
+    if !world.is_changed() && !loc.is_changed() && new_panel.is_empty() {
+        return;
+    }
+
+    // Positions come from the whole graph, so rooms don't move as more get discovered
+    let layers = world.graph.layers();
+    let max_rows = layers.iter().map(Vec::len).max().unwrap_or(1);
+    let position = |room: usize| -> Option<Vec2> {
+        layers.iter().enumerate().find_map(|(column, layer)| {
+            let row = layer.iter().position(|&r| r == room)?;
+            let offset = (max_rows - layer.len()) as f32 * ROW_HEIGHT / 2.0;
+            Some(Vec2::new(column as f32 * COLUMN_WIDTH, offset + row as f32 * ROW_HEIGHT))
+        })
+    };
+    let is_shown = |room: usize| world.visited.contains(&room) || world.is_known(room);
+
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    for (panel, mut node) in panel_query.iter_mut() {
+        node.width = Val::Px(layers.len() as f32 * COLUMN_WIDTH - (COLUMN_WIDTH - ROOM_WIDTH));
+        node.height = Val::Px(max_rows as f32 * ROW_HEIGHT - (ROW_HEIGHT - ROOM_HEIGHT));
+        commands.entity(panel).despawn_related::<Children>();
+
+        commands.entity(panel).with_children(|panel| {
+            // Connections first, so the room boxes are drawn on top
+            for &room in world.visited.iter() {
+                let Some(from) = position(room) else { continue };
+                for exit in world.graph.exits(room) {
+                    let Some(to) = position(exit.to).filter(|_| is_shown(exit.to)) else { continue };
+                    let color = if world.visited.contains(&exit.to) {
+                        Color::srgb(0.7, 0.7, 0.8)
+                    } else {
+                        Color::srgb(0.3, 0.3, 0.35)
+                    };
+                    spawn_connection(panel, from, to, color);
+                }
+            }
+
+            for room in layers.iter().flatten().copied().filter(|&room| is_shown(room)) {
+                let Some(at) = position(room) else { continue };
+                spawn_room(panel, &font, &loc, &world, &boss_scripts, room, at);
+            }
+        });
+    }
+}
+
+// Key prompt below the map, follows rebinding and the input device
+pub fn update_dungeon_map_hint(
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
+    mut query: Query<(&mut Text, Ref<DungeonMapHint>)>,
+) {
+    for (mut text, hint) in query.iter_mut() {
+        if prompts.is_changed() || hint.is_added() {
+            **text = loc.t_with("map.hint", &[("key", &prompts.label(GameAction::ToggleMap))]);
+        }
+    }
+}
+
+// Straight line between two room centers, rotated around its own center
+fn spawn_connection(panel: &mut ChildSpawnerCommands<'_>, from: Vec2, to: Vec2, color: Color) {
+    let delta = to - from;
+    let center = (from + to) / 2.0 + Vec2::new(ROOM_WIDTH, ROOM_HEIGHT) / 2.0;
+    let length = delta.length();
+    panel.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(center.x - length / 2.0),
+            top: Val::Px(center.y - 1.5),
+            width: Val::Px(length),
+            height: Val::Px(3.0),
+            ..default()
+        },
+        UiTransform::from_rotation(Rot2::radians(delta.y.atan2(delta.x))),
+        BackgroundColor(color),
+    ));
+}
+
+fn spawn_room(
+    panel: &mut ChildSpawnerCommands<'_>,
+    font: &Handle<Font>,
+    loc: &Localization,
+    world: &WorldState,
+    boss_scripts: &BossScripts,
+    index: usize,
+    at: Vec2,
+) {
+    let room = &world.graph.rooms[index];
+    let is_current = index == world.current_index();
+    let is_visited = world.visited.contains(&index);
+
+    // Known rooms only show that there is something behind the door
+    let (icons, name) = if is_visited {
+        (room_icons(room, boss_scripts), loc.t(room.kind.name_key()))
+    } else {
+        ("?".to_string(), loc.t("map.unknown_room"))
+    };
+    let border = if is_current {
+        Color::srgb(1.0, 0.84, 0.0)
+    } else if is_visited {
+        Color::srgb(0.5, 0.5, 0.6)
+    } else {
+        Color::srgb(0.25, 0.25, 0.3)
+    };
+    let text_color = if is_visited { Color::WHITE } else { Color::srgb(0.5, 0.5, 0.55) };
+    let marker = if is_current { "▶ " } else { "" };
+
+    panel.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(at.x),
+            top: Val::Px(at.y),
+            width: Val::Px(ROOM_WIDTH),
+            height: Val::Px(ROOM_HEIGHT),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(if is_current { 3.0 } else { 2.0 })),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.1, 0.1, 0.14)),
+        BorderColor::all(border),
+    ))
+    .with_children(|room_box| {
+        room_box.spawn((
+            Text::new(icons),
+            TextFont {
+                font: font.clone(),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(text_color),
+        ));
+        room_box.spawn((
+            Text::new(format!("{}{}", marker, name)),
+            TextFont {
+                font: font.clone(),
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(text_color),
+        ));
+    });
+}
+
+// ⚔ enemies, ★ upgrade, ☠ boss, ✚ rest
+fn room_icons(room: &Room, boss_scripts: &BossScripts) -> String {
+    let mut icons = Vec::new();
+    if !room.enemies.is_empty() {
+        icons.push("⚔");
+    }
+    if room.upgrade.is_some() {
+        icons.push("★");
+    }
+    if room.enemies.iter().any(|&enemy| boss_scripts.is_boss(enemy)) {
+        icons.push("☠");
+    }
+    if icons.is_empty() {
+        icons.push("✚");
+    }
+    icons.join(" ")
+}
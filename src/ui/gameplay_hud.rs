@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use crate::character::*;
-use crate::combat::events::*;
 use crate::world::WorldState;
-use super::{StoryText, CombatLog};
+use crate::input::{ActionPrompts, ActionState, GameAction};
+use crate::localization::{Localization, LocalizedText};
+use super::{StoryText, CombatLog, spawn_log_spans};
 
 #[derive(Component)]
 pub struct GameplayHudMarker;
@@ -19,15 +20,28 @@ pub struct CombatLogText;
 #[derive(Component)]
 pub struct StoryTextDisplay;
 
+// Result of the last log export, shown next to the log for a few seconds
+#[derive(Component)]
+pub struct LogExportStatus(pub Timer);
+
 #[derive(Component)]
 pub struct RoomInfoText;
 
 #[derive(Component)]
 pub struct PlayerStatsText;
 
+// Doors offered after a victory, rebuilt by update_door_choices
+#[derive(Component)]
+pub struct DoorChoiceList;
+
+// Index into the exits of the current room
+#[derive(Component)]
+pub struct DoorButton(pub usize);
+
 pub fn setup_gameplay_hud(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    loc: Res<Localization>,
 ) {
     let font = asset_server.load("fonts/atlantisheadbold.ttf");
     
@@ -60,7 +74,7 @@ pub fn setup_gameplay_hud(
             })
             .with_children(|left| {
                 left.spawn((
-                    Text::new("Raum 1 von 8"),
+                    Text::new(""),
                     TextFont {
                         font: font.clone(),
                         font_size: 24.0,
@@ -79,7 +93,7 @@ pub fn setup_gameplay_hud(
             })
             .with_children(|right| {
                 right.spawn((
-                    Text::new("Stats werden geladen..."),
+                    Text::new(loc.t("hud.loading_stats")),
                     TextFont {
                         font: font.clone(),
                         font_size: 18.0,
@@ -107,13 +121,14 @@ pub fn setup_gameplay_hud(
         .with_children(|hp_container| {
             // HP label
             hp_container.spawn((
-                Text::new("♥ GESUNDHEIT"),
+                Text::new(loc.t("hud.health")),
                 TextFont {
                     font: font.clone(),
                     font_size: 22.0,
                     ..default()
                 },
                 TextColor(Color::srgb(1.0, 0.3, 0.3)),
+                LocalizedText("hud.health"),
                 Node {
                     margin: UiRect::bottom(Val::Px(8.0)),
                     ..default()
@@ -165,7 +180,8 @@ pub fn setup_gameplay_hud(
         parent.spawn((
             Node {
                 width: Val::Percent(100.0),
-                height: Val::Px(200.0),
+                min_height: Val::Px(200.0),
+                flex_direction: FlexDirection::Column,
                 padding: UiRect::all(Val::Px(20.0)),
                 margin: UiRect::bottom(Val::Px(20.0)),
                 border: UiRect::all(Val::Px(2.0)),
@@ -176,7 +192,7 @@ pub fn setup_gameplay_hud(
         ))
         .with_children(|story_section| {
             story_section.spawn((
-                Text::new("Lade Geschichte..."),
+                Text::new(loc.t("hud.loading_story")),
                 TextFont {
                     font: font.clone(),
                     font_size: 20.0,
@@ -185,6 +201,15 @@ pub fn setup_gameplay_hud(
                 TextColor(Color::srgb(1.0, 0.95, 0.7)),
                 StoryTextDisplay,
             ));
+            story_section.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    margin: UiRect::top(Val::Px(10.0)),
+                    ..default()
+                },
+                DoorChoiceList,
+            ));
         });
 
         // Combat log section
@@ -202,7 +227,17 @@ pub fn setup_gameplay_hud(
         ))
         .with_children(|log_section| {
             log_section.spawn((
-                Text::new("=== KAMPF LOG ===\nDrücke ENTER um den Kampf zu starten..."),
+                Text::new(""),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 1.0, 0.5)),
+                LogExportStatus(Timer::from_seconds(4.0, TimerMode::Once)),
+            ));
+            log_section.spawn((
+                Text::new(loc.t("hud.log_title")),
                 TextFont {
                     font,
                     font_size: 16.0,
@@ -239,87 +274,212 @@ pub fn update_hp_bar(
 // Update room info
 pub fn update_room_info(
     world: Res<WorldState>,
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
     mut query: Query<&mut Text, With<RoomInfoText>>,
 ) {
     for mut text in query.iter_mut() {
-        **text = format!(
-            "📍 {} - Raum {} von {}",
-            world.current_room().name(),
-            world.current_room_index + 1,
-            world.total_rooms
-        );
+        **text = loc.t_with("hud.room_info", &[
+            ("room", &loc.t(world.current_room().name_key())),
+            ("progress", &world.progress(&loc)),
+            ("map", &prompts.label(GameAction::ToggleMap)),
+        ]);
+        text.push('\n');
+        text.push_str(&world.difficulty_text(&loc));
+        // Generated dungeons show their seed, so the run can be shared
+        if let Some(seed) = world.seed {
+            text.push('\n');
+            text.push_str(&loc.t_with("hud.seed", &[("seed", &seed)]));
+        }
+    }
+}
+
+// Show the doors of the current room while the player picks one
+pub fn update_door_choices(
+    mut commands: Commands,
+    world: Res<WorldState>,
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
+    asset_server: Res<AssetServer>,
+    list_query: Query<Entity, With<DoorChoiceList>>,
+    new_list: Query<(), Added<DoorChoiceList>>,
+) {
+    if !world.is_changed() && !prompts.is_changed() && new_list.is_empty() {
+        return;
+    }
+    
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    for list in list_query.iter() {
+        commands.entity(list).despawn_related::<Children>();
+        let Some(selected) = world.door_choice else {
+            continue;
+        };
+        
+        commands.entity(list).with_children(|list| {
+            for (index, exit) in world.exits().iter().enumerate() {
+                let is_selected = index == selected;
+                let marker = if is_selected { "> " } else { "" };
+                list.spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(if is_selected {
+                        Color::srgb(0.2, 0.2, 0.25)
+                    } else {
+                        Color::srgb(0.12, 0.12, 0.15)
+                    }),
+                    DoorButton(index),
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new(format!("{}{}. {}", marker, index + 1, loc.t(&exit.hint))),
+                        TextFont {
+                            font: font.clone(),
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.85, 0.6)),
+                    ));
+                });
+            }
+            list.spawn((
+                Text::new(loc.t_with("hud.door_prompt", &[
+                    ("select", &prompts.pair(GameAction::Up, GameAction::Down)),
+                    ("confirm", &prompts.label(GameAction::Confirm)),
+                    ("camp", &prompts.label(GameAction::Camp)),
+                    ("left", &world.camps_left),
+                ])),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 0.6, 0.7)),
+            ));
+        });
     }
 }
 
 // Update player stats display
 pub fn update_player_stats(
     player_query: Query<(&Attack, &Defense, &Damage, &Armor), With<Player>>,
+    loc: Res<Localization>,
     mut query: Query<&mut Text, With<PlayerStatsText>>,
 ) {
     if let Ok((attack, defense, damage, armor)) = player_query.single() {
         for mut text in query.iter_mut() {
-            **text = format!(
-                "⚔️ Angriff: {} | 🛡️ Verteidigung: {} | 💥 Schaden: {} | 🔰 Rüstung: {}",
-                attack.0, defense.0, damage.0, armor.0
-            );
+            **text = loc.t_with("hud.player_stats", &[
+                ("attack", &attack.0),
+                ("defense", &defense.0),
+                ("damage", &damage.0),
+                ("armor", &armor.0),
+            ]);
         }
     }
 }
 
-// Update combat log with messages from events
+// Show the combat log (filled by play_combat_messages)
 pub fn update_combat_log(
-    mut combat_log: ResMut<CombatLog>,
-    mut message_events: MessageReader<CombatMessageEvent>,
-    mut query: Query<&mut Text, With<CombatLogText>>,
+    mut commands: Commands,
+    combat_log: Res<CombatLog>,
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
+    mut query: Query<(Entity, &mut Text, &TextFont), With<CombatLogText>>,
+    new_log_text: Query<(), Added<CombatLogText>>,
 ) {
-    // Add new messages
-    for event in message_events.read() {
-        combat_log.messages.push(event.message.clone());
-        
-        // Keep only last 10 messages
-        if combat_log.messages.len() > 10 {
-            combat_log.messages.remove(0);
+    // Redraw when the log, the input device or the language changed, or the HUD was just spawned
+    if !combat_log.is_changed() && !prompts.is_changed() && !loc.is_changed() && new_log_text.is_empty() {
+        return;
+    }
+    
+    for (entity, mut text, font) in query.iter_mut() {
+        if combat_log.entries.is_empty() {
+            **text = loc.t_with("hud.log_start_combat", &[("key", &prompts.label(GameAction::Confirm))]);
+            continue;
         }
+        **text = loc.t_with("hud.log_header", &[
+            ("scroll", &prompts.pair(GameAction::ScrollUp, GameAction::ScrollDown)),
+            ("export", &prompts.label(GameAction::ExportLog)),
+        ]);
+        spawn_log_spans(&mut commands, entity, font, combat_log.visible_entries());
+    }
+}
+
+// Export the log of the last fight to a text file
+pub fn export_combat_log(
+    actions: Res<ActionState>,
+    loc: Res<Localization>,
+    combat_log: Res<CombatLog>,
+    mut status_query: Query<(&mut Text, &mut LogExportStatus)>,
+) {
+    if !actions.just_pressed(GameAction::ExportLog) || combat_log.entries.is_empty() {
+        return;
     }
     
-    // Update display
-    if !combat_log.messages.is_empty() {
-        for mut text in query.iter_mut() {
-            let log_text = format!(
-                "=== KAMPF LOG ===\n{}",
-                combat_log.messages.join("\n")
-            );
-            **text = log_text;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let path = std::path::PathBuf::from(format!("logs/kampflog_{}.txt", timestamp));
+    
+    let message = match combat_log.export(&path, &loc) {
+        Ok(()) => {
+            info!("Exported combat log to {}", path.display());
+            loc.t_with("hud.log_exported", &[("path", &path.display())])
+        }
+        Err(err) => {
+            warn!("Could not export combat log to {}: {}", path.display(), err);
+            loc.t_with("hud.log_export_failed", &[("error", &err)])
+        }
+    };
+    // Shown beside the log, not in it, so later exports don't contain it
+    for (mut text, mut status) in status_query.iter_mut() {
+        **text = message.clone();
+        status.0.reset();
+    }
+}
+
+// Hide the export result again after a few seconds
+pub fn update_log_export_status(time: Res<Time>, mut status_query: Query<(&mut Text, &mut LogExportStatus)>) {
+    for (mut text, mut status) in status_query.iter_mut() {
+        if status.0.tick(time.delta()).just_finished() {
+            text.clear();
         }
     }
 }
 
-// Typewriter effect for story text
+// Typewriter effect for story text, confirm or a click reveals the rest at once
 pub fn update_story_text_typewriter(
     time: Res<Time>,
+    actions: Res<ActionState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
     mut story: ResMut<StoryText>,
     mut query: Query<&mut Text, With<StoryTextDisplay>>,
 ) {
-    if story.visible_chars < story.full_text.len() {
-        story.timer.tick(time.delta());
-        
-        if story.timer.is_finished() {
-            story.visible_chars += 1;
-            story.timer.reset();
+    if !story.is_finished() {
+        // A text set this frame (e.g. by picking a door with confirm) is not skipped right away
+        let skip = story.is_started() && (actions.just_pressed(GameAction::Confirm)
+            || mouse.just_pressed(MouseButton::Left)
+            || touches.any_just_pressed());
+        if skip {
+            story.reveal_all();
+        } else {
+            story.tick(time.delta());
         }
-        
-        // Update display
-        for mut text in query.iter_mut() {
-            let mut display_text: String = story.full_text.chars()
-                .take(story.visible_chars)
-                .collect();
-            
-            // Add blinking cursor if not finished
-            if story.visible_chars < story.full_text.len() {
-                display_text.push('▋');
-            }
-            
-            **text = display_text;
+    }
+    
+    let mut display_text = story.visible_text().to_string();
+    // Add blinking cursor if not finished
+    if !story.is_finished() {
+        display_text.push('▋');
+    }
+    
+    for mut text in query.iter_mut() {
+        if **text != display_text {
+            **text = display_text.clone();
         }
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 use crate::game_state::GameState;
+use crate::input::{ActionPrompts, GameAction, MenuSelection, SeedInput};
+use crate::localization::{Localization, LocalizedText};
+use crate::world::RunSetup;
 
 #[derive(Component)]
 pub struct MainMenuMarker;
@@ -7,7 +10,11 @@ pub struct MainMenuMarker;
 #[derive(Component)]
 pub struct MainMenuButton(pub usize);
 
-pub fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+// Seed line below the random dungeon button
+#[derive(Component)]
+pub struct SeedText;
+
+pub fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, loc: Res<Localization>) {
     let font = asset_server.load("fonts/atlantisheadbold.ttf");
 
     commands.spawn((
@@ -47,16 +54,79 @@ pub fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ..default()
             },
             BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-            MainMenuButton(0),
+            MainMenuButton(MenuSelection::START),
+        ))
+        .with_child((
+            Text::new(loc.t("menu.start")),
+            TextFont {
+                font: font.clone(),
+                font_size: 32.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            LocalizedText("menu.start"),
+        ));
+
+        // Random Dungeon Button
+        parent.spawn((
+            Button,
+            Node {
+                width: Val::Auto,
+                height: Val::Px(45.0),
+                margin: UiRect::new(Val::Px(8.0), Val::Px(8.0), Val::Px(8.0), Val::Px(0.0)),
+                padding: UiRect::all(Val::Px(10.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            MainMenuButton(MenuSelection::RANDOM_DUNGEON),
+        ))
+        .with_child((
+            Text::new(loc.t("menu.random_dungeon")),
+            TextFont {
+                font: font.clone(),
+                font_size: 32.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            LocalizedText("menu.random_dungeon"),
+        ));
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font: font.clone(),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.6, 0.6, 0.7)),
+            SeedText,
+        ));
+
+        // Settings Button
+        parent.spawn((
+            Button,
+            Node {
+                width: Val::Auto,
+                height: Val::Px(45.0),
+                margin: UiRect::all(Val::Px(8.0)),
+                padding: UiRect::all(Val::Px(10.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            MainMenuButton(MenuSelection::SETTINGS),
         ))
         .with_child((
-            Text::new("Neues Spiel starten"),
+            Text::new(loc.t("menu.settings")),
             TextFont {
                 font: font.clone(),
                 font_size: 32.0,
                 ..default()
             },
             TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            LocalizedText("menu.settings"),
         ));
 
         // Quit Button
@@ -72,16 +142,17 @@ pub fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ..default()
             },
             BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-            MainMenuButton(1),
+            MainMenuButton(MenuSelection::QUIT),
         ))
         .with_child((
-            Text::new("Spiel beenden"),
+            Text::new(loc.t("menu.quit")),
             TextFont {
                 font,
                 font_size: 32.0,
                 ..default()
             },
             TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            LocalizedText("menu.quit"),
         ));
     });
 }
@@ -92,6 +163,8 @@ pub fn update_main_menu_buttons(
         (Changed<Interaction>, With<Button>),
     >,
     mut next_state: ResMut<NextState<GameState>>,
+    mut seed_input: ResMut<SeedInput>,
+    mut run_setup: ResMut<RunSetup>,
 ) {
     for (interaction, mut color, button) in interaction_query.iter_mut() {
         match *interaction {
@@ -103,11 +176,59 @@ pub fn update_main_menu_buttons(
             }
             Interaction::Pressed => {
                 match button.0 {
-                    0 => next_state.set(GameState::CharacterSelection),
-                    1 => std::process::exit(0),
+                    MenuSelection::START => {
+                        run_setup.seed = None;
+                        next_state.set(GameState::CharacterSelection);
+                    }
+                    MenuSelection::RANDOM_DUNGEON => {
+                        run_setup.seed = Some(seed_input.seed());
+                        next_state.set(GameState::CharacterSelection);
+                    }
+                    MenuSelection::SETTINGS => next_state.set(GameState::Settings),
+                    MenuSelection::QUIT => std::process::exit(0),
                     _ => {}
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// Highlight the button picked with keyboard or gamepad
+pub fn highlight_selected_menu_button(
+    menu_selection: Res<MenuSelection>,
+    mut button_query: Query<(&MainMenuButton, &mut BackgroundColor)>,
+    new_buttons: Query<(), Added<MainMenuButton>>,
+) {
+    if !menu_selection.is_changed() && new_buttons.is_empty() {
+        return;
+    }
+    
+    for (button, mut color) in button_query.iter_mut() {
+        *color = if button.0 == menu_selection.current() {
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.25))
+        } else {
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15))
+        };
+    }
+}
+
+// Show the seed being typed, with a hint while the random dungeon is selected
+pub fn update_seed_text(
+    seed_input: Res<SeedInput>,
+    menu_selection: Res<MenuSelection>,
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
+    mut query: Query<(&mut Text, Ref<SeedText>)>,
+) {
+    for (mut text, seed_text) in query.iter_mut() {
+        if !seed_input.is_changed() && !menu_selection.is_changed() && !prompts.is_changed() && !seed_text.is_added() {
+            continue;
+        }
+        let seed = if seed_input.digits.is_empty() { "-" } else { seed_input.digits.as_str() };
+        **text = if menu_selection.current() == MenuSelection::RANDOM_DUNGEON {
+            loc.t_with("menu.seed_edit", &[("seed", &seed), ("delete", &prompts.label(GameAction::DeleteChar))])
+        } else {
+            loc.t_with("menu.seed", &[("seed", &seed)])
+        };
+    }
+}
@@ -1,15 +1,29 @@
+use std::time::Duration;
 use bevy::prelude::*;
+use unicode_segmentation::UnicodeSegmentation;
 use crate::game_state::GameState;
+use crate::combat::events::MessageType;
+use crate::localization::Localization;
 
 pub mod main_menu;
 pub mod character_select;
 pub mod gameplay_hud;
+pub mod dungeon_map;
 pub mod combat_ui;
+pub mod combat_feedback;
+pub mod combat_scene;
+pub mod settings_menu;
+pub mod run_summary;
 
 pub use main_menu::*;
 pub use character_select::*;
 pub use gameplay_hud::*;
+pub use dungeon_map::*;
 pub use combat_ui::*;
+pub use combat_feedback::*;
+pub use combat_scene::*;
+pub use settings_menu::*;
+pub use run_summary::*;
 
 pub fn plugin(app: &mut App) {
     app
@@ -17,36 +31,99 @@ pub fn plugin(app: &mut App) {
         .init_resource::<StoryText>()
         .init_resource::<CombatLog>()
         .init_resource::<CombatUIState>()
+        .init_resource::<ScreenShake>()
+        .init_resource::<SettingsMenuState>()
+        .init_resource::<RunSummaryMenu>()
         
         // Main menu systems
         .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
-        .add_systems(Update, update_main_menu_buttons.run_if(in_state(GameState::MainMenu)))
+        .add_systems(Update, (
+            update_main_menu_buttons,
+            highlight_selected_menu_button,
+            update_seed_text,
+        ).run_if(in_state(GameState::MainMenu)))
         .add_systems(OnExit(GameState::MainMenu), cleanup_menu::<MainMenuMarker>)
         
         // Character selection systems
         .add_systems(OnEnter(GameState::CharacterSelection), setup_character_select)
-        .add_systems(Update, update_character_select_buttons.run_if(in_state(GameState::CharacterSelection)))
+        .add_systems(Update, (
+            update_character_select_buttons,
+            highlight_selected_character_button,
+            update_difficulty_text,
+            update_permadeath_text,
+            update_permadeath_button,
+        ).run_if(in_state(GameState::CharacterSelection)))
         .add_systems(OnExit(GameState::CharacterSelection), cleanup_menu::<CharSelectMarker>)
         
+        // Settings menu systems
+        .add_systems(OnEnter(GameState::Settings), setup_settings_menu)
+        .add_systems(Update, (
+            handle_settings_input,
+            show_settings_save_error,
+            update_settings_menu,
+        ).chain().run_if(in_state(GameState::Settings)))
+        .add_systems(OnExit(GameState::Settings), cleanup_menu::<SettingsMenuMarker>)
+        
+        // Game over and victory screens
+        .add_systems(OnEnter(GameState::GameOver), setup_run_summary)
+        .add_systems(OnEnter(GameState::Victory), setup_run_summary)
+        .add_systems(Update, (
+            handle_run_summary_input,
+            highlight_run_summary_buttons,
+        ).chain().run_if(in_state(GameState::GameOver).or(in_state(GameState::Victory))))
+        .add_systems(OnExit(GameState::GameOver), cleanup_menu::<RunSummaryMarker>)
+        .add_systems(OnExit(GameState::Victory), cleanup_menu::<RunSummaryMarker>)
+        
         // Gameplay HUD systems
-        .add_systems(OnEnter(GameState::Gameplay), setup_gameplay_hud)
+        .add_systems(OnEnter(GameState::Gameplay), (setup_gameplay_hud, setup_dungeon_map))
         .add_systems(Update, (
             update_hp_bar,
             update_room_info,
             update_player_stats,
             update_combat_log,
-            update_story_text_typewriter,
+            update_door_choices,
+            update_dungeon_map,
+            update_dungeon_map_hint,
+            toggle_dungeon_map.run_if(resource_changed::<crate::input::ShowMap>),
+            export_combat_log,
+            update_log_export_status,
+            // after the combat start check, so the confirm that skips the text doesn't also start the fight
+            update_story_text_typewriter.after(crate::world::start_combat_when_ready_system),
         ).run_if(in_state(GameState::Gameplay)))
         .add_systems(OnExit(GameState::Gameplay), cleanup_menu::<GameplayHudMarker>)
         
+        // Combat message playback (also drains the last messages after combat)
+        .add_systems(Update, play_combat_messages.before(crate::combat::process_turn_system))
+        .add_systems(Update, scroll_combat_log.run_if(
+            in_state(GameState::Gameplay).or(in_state(GameState::Combat))
+        ))
+        
         // Combat UI systems
-        .add_systems(OnEnter(GameState::Combat), setup_combat_ui)
+        .add_systems(OnEnter(GameState::Combat), (setup_combat_ui, reset_combat_log))
         .add_systems(Update, (
             handle_combat_ui_input,
+            handle_combat_pointer_input,
+            update_combat_button_colors,
             update_combat_ui,
+            update_enemy_cards.run_if(enemy_cards_changed),
+            update_combat_action_buttons.run_if(combat_action_buttons_changed),
+            update_enemy_tooltip,
+            update_turn_timeline.run_if(turn_timeline_changed),
             update_combat_log_display,
+            spawn_attack_feedback,
+            animate_floating_text,
+            apply_screen_shake,
         ).run_if(in_state(GameState::Combat)))
-        .add_systems(OnExit(GameState::Combat), cleanup_menu::<CombatUIMarker>);
+        .add_systems(Update, (
+            spawn_combat_sprites,
+            apply_sprite_fallbacks,
+            trigger_sprite_animations,
+            animate_combat_sprites,
+        ).chain().run_if(in_state(GameState::Combat)))
+        .add_systems(OnExit(GameState::Combat), (
+            cleanup_menu::<CombatUIMarker>,
+            cleanup_menu::<CombatSprite>,
+        ));
 }
 
 // Generic cleanup system
@@ -57,24 +134,168 @@ fn cleanup_menu<T: Component>(mut commands: Commands, query: Query<Entity, With<
 }
 
 // Resources
+
+// Story text revealed one grapheme at a time, so umlauts and emoji count as one step
 #[derive(Resource)]
 pub struct StoryText {
-    pub full_text: String,
-    pub visible_chars: usize,
-    pub timer: Timer,
+    full_text: String,
+    grapheme_count: usize,
+    visible_graphemes: usize,
+    timer: Timer, // delay until the next grapheme, longer after punctuation
+    pub seconds_per_char: f32,
 }
 
 impl Default for StoryText {
     fn default() -> Self {
         Self {
             full_text: String::new(),
-            visible_chars: 0,
-            timer: Timer::from_seconds(0.05, TimerMode::Repeating),
+            grapheme_count: 0,
+            visible_graphemes: 0,
+            timer: Timer::from_seconds(0.05, TimerMode::Once),
+            seconds_per_char: 0.05,
+        }
+    }
+}
+
+impl StoryText {
+    // Replace the text and start revealing it from the beginning
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.full_text = text.into();
+        self.grapheme_count = self.full_text.graphemes(true).count();
+        self.visible_graphemes = 0;
+        self.timer = Timer::from_seconds(self.seconds_per_char, TimerMode::Once);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.visible_graphemes >= self.grapheme_count
+    }
+
+    // At least one grapheme is shown, so the player has seen the new text
+    pub fn is_started(&self) -> bool {
+        self.visible_graphemes > 0
+    }
+
+    pub fn reveal_all(&mut self) {
+        self.visible_graphemes = self.grapheme_count;
+    }
+
+    // Reveal the graphemes whose delay has passed
+    pub fn tick(&mut self, delta: Duration) {
+        let mut remaining = delta;
+        while !self.is_finished() {
+            let before = self.timer.elapsed();
+            self.timer.tick(remaining);
+            if !self.timer.is_finished() {
+                break;
+            }
+            remaining = remaining.saturating_sub(self.timer.duration() - before);
+
+            let mut upcoming = self.full_text.graphemes(true).skip(self.visible_graphemes);
+            let revealed = upcoming.next().unwrap_or("");
+            self.visible_graphemes += 1;
+            // Pause once after "..." or "?!", not after every mark
+            let factor = match upcoming.next() {
+                Some(next) if pause_factor(next) > 1.0 => 1.0,
+                _ => pause_factor(revealed),
+            };
+            let delay = self.seconds_per_char * factor;
+            self.timer = Timer::from_seconds(delay, TimerMode::Once);
         }
     }
+
+    // The part of the text revealed so far, cut at a grapheme boundary
+    pub fn visible_text(&self) -> &str {
+        let end = self.full_text
+            .grapheme_indices(true)
+            .nth(self.visible_graphemes)
+            .map(|(index, _)| index)
+            .unwrap_or(self.full_text.len());
+        &self.full_text[..end]
+    }
+}
+
+// Dramatic pauses: longer after the end of a sentence than after a comma
+fn pause_factor(grapheme: &str) -> f32 {
+    match grapheme {
+        "." | "!" | "?" | "…" => 8.0,
+        "," | ";" | ":" => 4.0,
+        "\n" => 6.0,
+        _ => 1.0,
+    }
+}
+
+// One line of the combat log
+#[derive(Clone)]
+pub struct CombatLogEntry {
+    pub message: String,
+    pub message_type: MessageType,
+    pub round: u32,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+}
+
+impl CombatLogEntry {
+    // Plain text line for the exported log
+    pub fn to_export_line(&self, loc: &Localization) -> String {
+        let participants = match (&self.actor, &self.target) {
+            (Some(actor), Some(target)) => format!("{} -> {}: ", actor, target),
+            (Some(actor), None) => format!("{}: ", actor),
+            _ => String::new(),
+        };
+        format!(
+            "[{}] [{:?}] {}{}",
+            loc.t_with("combat_ui.round", &[("round", &self.round)]),
+            self.message_type,
+            participants,
+            self.message
+        )
+    }
 }
 
 #[derive(Resource, Default)]
 pub struct CombatLog {
-    pub messages: Vec<String>,
+    pub entries: Vec<CombatLogEntry>, // full log of the current fight
+    pub scroll_offset: usize, // lines scrolled up from the newest entry
+}
+
+impl CombatLog {
+    pub const VISIBLE_LINES: usize = 10;
+
+    pub fn push(&mut self, entry: CombatLogEntry) {
+        self.entries.push(entry);
+        // Keep the view in place while scrolled up
+        if self.scroll_offset > 0 {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.scroll_offset = 0;
+    }
+
+    // Entries in the scroll window
+    pub fn visible_entries(&self) -> &[CombatLogEntry] {
+        let end = self.entries.len().saturating_sub(self.scroll_offset);
+        let start = end.saturating_sub(Self::VISIBLE_LINES);
+        &self.entries[start..end]
+    }
+
+    pub fn scroll_up(&mut self) {
+        let max_offset = self.entries.len().saturating_sub(Self::VISIBLE_LINES);
+        self.scroll_offset = (self.scroll_offset + Self::VISIBLE_LINES).min(max_offset);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(Self::VISIBLE_LINES);
+    }
+
+    // Write the full log to a text file
+    pub fn export(&self, path: &std::path::Path, loc: &Localization) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let lines: Vec<String> = self.entries.iter().map(|entry| entry.to_export_line(loc)).collect();
+        std::fs::write(path, lines.join("\n"))
+    }
 }
\ No newline at end of file
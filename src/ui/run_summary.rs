@@ -0,0 +1,209 @@
+use bevy::prelude::*;
+use crate::game_state::GameState;
+use crate::input::{ActionState, GameAction};
+use crate::localization::Localization;
+use crate::world::{restart_run, retry_room_system, RunStats, WorldState};
+
+#[derive(Component)]
+pub struct RunSummaryMarker;
+
+#[derive(Component)]
+pub struct RunSummaryButton(pub usize);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SummaryOption {
+    RetryRoom, // only after a defeat without permadeath
+    Restart,
+    MainMenu,
+}
+
+impl SummaryOption {
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            SummaryOption::RetryRoom => "summary.retry_room",
+            SummaryOption::Restart => "summary.restart",
+            SummaryOption::MainMenu => "summary.main_menu",
+        }
+    }
+}
+
+// Buttons of the game over or victory screen and the one picked with keyboard or gamepad
+#[derive(Resource, Default)]
+pub struct RunSummaryMenu {
+    pub options: Vec<SummaryOption>,
+    pub selected: usize,
+}
+
+pub fn setup_run_summary(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    state: Res<State<GameState>>,
+    world: Res<WorldState>,
+    stats: Res<RunStats>,
+    loc: Res<Localization>,
+    mut menu: ResMut<RunSummaryMenu>,
+) {
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    let victory = *state.get() == GameState::Victory;
+    let permadeath = world.permadeath;
+
+    let (title, title_color, text) = if victory {
+        ("summary.victory_title", Color::srgb(1.0, 0.85, 0.2), loc.t("summary.victory_text"))
+    } else if permadeath {
+        ("summary.game_over_title", Color::srgb(0.9, 0.2, 0.2), loc.t("summary.permadeath_text"))
+    } else {
+        ("summary.game_over_title", Color::srgb(0.9, 0.2, 0.2), loc.t("summary.defeated_text"))
+    };
+
+    menu.options = if victory || permadeath {
+        vec![SummaryOption::Restart, SummaryOption::MainMenu]
+    } else {
+        vec![SummaryOption::RetryRoom, SummaryOption::Restart, SummaryOption::MainMenu]
+    };
+    menu.selected = 0;
+
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.08, 0.08, 0.10)),
+        RunSummaryMarker,
+    ))
+    .with_children(|parent| {
+        // Title
+        parent.spawn((
+            Text::new(loc.t(title)),
+            TextFont {
+                font: font.clone(),
+                font_size: 60.0,
+                ..default()
+            },
+            TextColor(title_color),
+        ));
+
+        parent.spawn((
+            Text::new(text),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            TextLayout::new_with_justify(Justify::Center),
+            Node {
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+        ));
+
+        // Run summary
+        parent.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(15.0)),
+                margin: UiRect::bottom(Val::Px(15.0)),
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor::all(Color::srgb(0.4, 0.4, 0.5)),
+            BackgroundColor(Color::srgb(0.12, 0.12, 0.15)),
+        ))
+        .with_child((
+            Text::new(format!(
+                "{}\n{}",
+                world.difficulty_text(&loc),
+                stats.summary(&loc),
+            )),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+        ));
+
+        for (index, option) in menu.options.iter().enumerate() {
+            parent.spawn((
+                Button,
+                Node {
+                    width: Val::Auto,
+                    height: Val::Px(45.0),
+                    margin: UiRect::all(Val::Px(8.0)),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                RunSummaryButton(index),
+            ))
+            .with_child((
+                Text::new(loc.t(option.text_key())),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 32.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        }
+    });
+}
+
+// Pick an option with keyboard, gamepad or mouse
+pub fn handle_run_summary_input(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    mut menu: ResMut<RunSummaryMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+    button_query: Query<(&Interaction, &RunSummaryButton), Changed<Interaction>>,
+) {
+    let count = menu.options.len();
+    if count == 0 {
+        return;
+    }
+    if actions.just_pressed(GameAction::Up) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+    if actions.just_pressed(GameAction::Down) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+
+    let clicked = button_query.iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button.0);
+    let chosen = clicked.or(actions.just_pressed(GameAction::Confirm).then_some(menu.selected));
+    let Some(option) = chosen.and_then(|index| menu.options.get(index)) else {
+        return;
+    };
+
+    match option {
+        // Same room again with full health, the world stays as it is
+        SummaryOption::RetryRoom => {
+            commands.run_system_cached(retry_room_system);
+            next_state.set(GameState::Gameplay);
+        }
+        // Fresh dungeon and player with the same setup (seed, difficulty, character)
+        SummaryOption::Restart => {
+            restart_run(&mut commands);
+            next_state.set(GameState::Gameplay);
+        }
+        SummaryOption::MainMenu => next_state.set(GameState::MainMenu),
+    }
+}
+
+pub fn highlight_run_summary_buttons(
+    menu: Res<RunSummaryMenu>,
+    mut button_query: Query<(&RunSummaryButton, &mut BackgroundColor, &Interaction)>,
+) {
+    for (button, mut color, interaction) in button_query.iter_mut() {
+        color.set_if_neq(if button.0 == menu.selected || *interaction == Interaction::Hovered {
+            BackgroundColor(Color::srgb(0.25, 0.25, 0.25))
+        } else {
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15))
+        });
+    }
+}
@@ -0,0 +1,470 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use crate::game_state::GameState;
+use crate::input::*;
+use crate::localization::{Localization, LocalizedText};
+use crate::settings::{cycle, GameSettings, Language, TextSpeed, WindowModeSetting, COMBAT_SPEEDS, SettingsSaveError, UI_SCALES};
+
+#[derive(Component)]
+pub struct SettingsMenuMarker;
+
+#[derive(Component)]
+pub struct SettingsList;
+
+#[derive(Component)]
+pub struct SettingsRowButton(pub usize);
+
+#[derive(Component)]
+pub struct SettingsMessageText;
+
+// Entries of the settings menu: general settings in the left column,
+// key bindings in the right one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SettingsRow {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    TextSpeed,
+    CombatSpeed,
+    WindowMode,
+    Vsync,
+    UiScale,
+    Language,
+    Back,
+    Binding(GameAction),
+    ResetBindings,
+}
+
+impl SettingsRow {
+    pub fn is_binding_row(&self) -> bool {
+        matches!(self, SettingsRow::Binding(_) | SettingsRow::ResetBindings)
+    }
+}
+
+pub fn settings_rows() -> Vec<SettingsRow> {
+    let mut rows = vec![
+        SettingsRow::MasterVolume,
+        SettingsRow::MusicVolume,
+        SettingsRow::SfxVolume,
+        SettingsRow::TextSpeed,
+        SettingsRow::CombatSpeed,
+        SettingsRow::WindowMode,
+        SettingsRow::Vsync,
+        SettingsRow::UiScale,
+        SettingsRow::Language,
+        SettingsRow::Back,
+    ];
+    rows.extend(GameAction::REBINDABLE.iter().map(|action| SettingsRow::Binding(*action)));
+    rows.push(SettingsRow::ResetBindings);
+    rows
+}
+
+#[derive(Resource, Default)]
+pub struct SettingsMenuState {
+    pub selected: usize,
+    pub waiting_for_input: bool, // next key/button gets bound to the selected action
+    pub message: String,
+}
+
+pub fn setup_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut menu: ResMut<SettingsMenuState>,
+    loc: Res<Localization>,
+) {
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    *menu = SettingsMenuState::default();
+
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(30.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.12, 0.12, 0.15)),
+        SettingsMenuMarker,
+    ))
+    .with_children(|parent| {
+        // Title
+        parent.spawn((
+            Text::new(loc.t("settings.title")),
+            TextFont {
+                font: font.clone(),
+                font_size: 50.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.0, 1.0, 0.0)),
+            LocalizedText("settings.title"),
+            Node {
+                margin: UiRect::bottom(Val::Px(20.0)),
+                ..default()
+            },
+        ));
+
+        // Columns and rows, filled by update_settings_menu
+        parent.spawn((
+            Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(30.0),
+                ..default()
+            },
+            SettingsList,
+        ));
+
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font: font.clone(),
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 1.0, 0.5)),
+            Node {
+                margin: UiRect::top(Val::Px(15.0)),
+                ..default()
+            },
+            SettingsMessageText,
+        ));
+    });
+}
+
+// Raw keys and buttons, read while waiting for a new binding
+#[derive(SystemParam)]
+pub struct RawInput<'w, 's> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl RawInput<'_, '_> {
+    fn escape_pressed(&self) -> bool {
+        self.keyboard.just_pressed(KeyCode::Escape)
+    }
+
+    // First key or gamepad button pressed this frame
+    fn just_pressed(&self) -> Option<InputBinding> {
+        self.keyboard.get_just_pressed()
+            .next()
+            .map(|key| InputBinding::Key(*key))
+            .or_else(|| {
+                self.gamepads.iter()
+                    .find_map(|gamepad| gamepad.get_just_pressed().next().copied())
+                    .map(InputBinding::Gamepad)
+            })
+    }
+}
+
+// Navigate the rows, change settings, start rebinding and capture the new key or button
+pub fn handle_settings_input(
+    raw_input: RawInput,
+    actions: Res<ActionState>,
+    mut menu: ResMut<SettingsMenuState>,
+    mut bindings: ResMut<InputBindings>,
+    mut settings: ResMut<GameSettings>,
+    loc: Res<Localization>,
+    mut next_state: ResMut<NextState<GameState>>,
+    row_query: Query<(&Interaction, &SettingsRowButton), Changed<Interaction>>,
+) {
+    let rows = settings_rows();
+
+    if menu.waiting_for_input {
+        let SettingsRow::Binding(action) = rows[menu.selected] else {
+            menu.waiting_for_input = false;
+            return;
+        };
+
+        // Escape cancels, so it can't be bound here
+        if raw_input.escape_pressed() {
+            menu.waiting_for_input = false;
+            menu.message = loc.t("settings.cancelled");
+            return;
+        }
+
+        let Some(binding) = raw_input.just_pressed() else {
+            return;
+        };
+
+        menu.waiting_for_input = false;
+        menu.message = match bindings.rebind(action, binding) {
+            RebindResult::Bound => loc.t_with("settings.bound", &[
+                ("action", &action.name(&loc)),
+                ("binding", &binding.label(&loc)),
+            ]),
+            RebindResult::Swapped(other) => loc.t_with("settings.swapped", &[
+                ("action", &action.name(&loc)),
+                ("binding", &binding.label(&loc)),
+                ("other", &other.name(&loc)),
+            ]),
+            RebindResult::Conflict(other) => loc.t_with("settings.conflict", &[
+                ("binding", &binding.label(&loc)),
+                ("other", &other.name(&loc)),
+            ]),
+        };
+        save_bindings(&bindings, &mut menu, &loc);
+        return;
+    }
+
+    let mut activate = false;
+    let row = rows[menu.selected];
+    if !row.is_binding_row() && row != SettingsRow::Back {
+        let left = actions.just_pressed(GameAction::Left);
+        if left || actions.just_pressed(GameAction::Right) {
+            adjust_setting(&mut settings, row, !left, false);
+        }
+    }
+    if actions.just_pressed(GameAction::Up) {
+        menu.selected = if menu.selected == 0 { rows.len() - 1 } else { menu.selected - 1 };
+    }
+    if actions.just_pressed(GameAction::Down) {
+        menu.selected = (menu.selected + 1) % rows.len();
+    }
+    if actions.just_pressed(GameAction::Confirm) {
+        activate = true;
+    }
+    if actions.just_pressed(GameAction::Back) {
+        next_state.set(GameState::MainMenu);
+        return;
+    }
+    for (interaction, row) in row_query.iter() {
+        if *interaction == Interaction::Pressed {
+            menu.selected = row.0;
+            activate = true;
+        }
+    }
+
+    if !activate {
+        return;
+    }
+    match rows[menu.selected] {
+        SettingsRow::Binding(action) => {
+            menu.waiting_for_input = true;
+            menu.message = loc.t_with("settings.waiting_for_input", &[("action", &action.name(&loc))]);
+        }
+        SettingsRow::ResetBindings => {
+            *bindings = InputBindings::default();
+            menu.message = loc.t("settings.bindings_reset");
+            save_bindings(&bindings, &mut menu, &loc);
+        }
+        SettingsRow::Back => next_state.set(GameState::MainMenu),
+        row => {
+            // Confirm or a click steps forward and wraps around
+            adjust_setting(&mut settings, row, true, true);
+        }
+    }
+}
+
+// Step the value of a general setting; volumes only wrap when `wrap` is set
+fn adjust_setting(settings: &mut GameSettings, row: SettingsRow, forward: bool, wrap: bool) {
+    match row {
+        SettingsRow::MasterVolume => step_volume(&mut settings.master_volume, forward, wrap),
+        SettingsRow::MusicVolume => step_volume(&mut settings.music_volume, forward, wrap),
+        SettingsRow::SfxVolume => step_volume(&mut settings.sfx_volume, forward, wrap),
+        SettingsRow::TextSpeed => {
+            settings.text_speed = cycle(&TextSpeed::ALL, settings.text_speed, forward);
+        }
+        SettingsRow::CombatSpeed => {
+            settings.combat_speed = cycle(&COMBAT_SPEEDS, settings.combat_speed, forward);
+        }
+        SettingsRow::WindowMode => {
+            settings.window_mode = cycle(&WindowModeSetting::ALL, settings.window_mode, forward);
+        }
+        SettingsRow::Vsync => settings.vsync = !settings.vsync,
+        SettingsRow::UiScale => settings.ui_scale = cycle(&UI_SCALES, settings.ui_scale, forward),
+        SettingsRow::Language => settings.language = cycle(&Language::ALL, settings.language, forward),
+        SettingsRow::Back | SettingsRow::Binding(_) | SettingsRow::ResetBindings => {}
+    }
+}
+
+fn step_volume(volume: &mut f32, forward: bool, wrap: bool) {
+    const STEP: f32 = 0.1;
+    if wrap && forward && *volume >= 1.0 {
+        *volume = 0.0;
+        return;
+    }
+    let next = if forward { *volume + STEP } else { *volume - STEP };
+    // Round to whole steps so repeated presses don't drift
+    *volume = ((next / STEP).round() * STEP).clamp(0.0, 1.0);
+}
+
+// The settings save themselves when they change, only a failure is shown here
+pub fn show_settings_save_error(
+    save_error: Res<SettingsSaveError>,
+    mut menu: ResMut<SettingsMenuState>,
+    loc: Res<Localization>,
+) {
+    if !save_error.is_changed() {
+        return;
+    }
+    if let Some(err) = &save_error.0 {
+        menu.message = loc.t_with("settings.save_failed", &[("error", err)]);
+    }
+}
+
+fn save_bindings(bindings: &InputBindings, menu: &mut SettingsMenuState, loc: &Localization) {
+    if let Err(err) = bindings.save(BINDINGS_PATH) {
+        warn!("Could not save key bindings to {}: {}", BINDINGS_PATH, err);
+        menu.message = loc.t_with("settings.save_failed", &[("error", &err)]);
+    }
+}
+
+// Rebuild the rows when the selection, a setting or a binding changed
+pub fn update_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    menu: Res<SettingsMenuState>,
+    bindings: Res<InputBindings>,
+    settings: Res<GameSettings>,
+    loc: Res<Localization>,
+    list_query: Query<(Entity, Ref<SettingsList>)>,
+    mut message_query: Query<&mut Text, With<SettingsMessageText>>,
+) {
+    let Ok((list, list_ref)) = list_query.single() else {
+        return;
+    };
+    let changed = menu.is_changed() || bindings.is_changed() || settings.is_changed() || loc.is_changed();
+    if !changed && !list_ref.is_added() {
+        return;
+    }
+
+    for mut text in message_query.iter_mut() {
+        **text = menu.message.clone();
+    }
+
+    let font = asset_server.load("fonts/atlantisheadbold.ttf");
+    commands.entity(list).despawn_related::<Children>();
+    commands.entity(list).with_children(|parent| {
+        for binding_column in [false, true] {
+            parent.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                ..default()
+            })
+            .with_children(|col| {
+                let heading = if binding_column {
+                    "settings.bindings_heading"
+                } else {
+                    "settings.general_heading"
+                };
+                col.spawn((
+                    Text::new(loc.t(heading)),
+                    TextFont {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.7, 0.7, 0.8)),
+                    Node {
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        ..default()
+                    },
+                ));
+
+                for (i, row) in settings_rows().into_iter().enumerate() {
+                    if row.is_binding_row() != binding_column {
+                        continue;
+                    }
+                    let selected = i == menu.selected;
+                    let background = if selected && menu.waiting_for_input {
+                        Color::srgb(0.5, 0.45, 0.0)
+                    } else if selected {
+                        Color::srgb(0.25, 0.25, 0.25)
+                    } else {
+                        Color::srgb(0.15, 0.15, 0.15)
+                    };
+                    let columns = row_columns(row, &settings, &bindings, &loc);
+                    spawn_settings_row(col, &font, i, columns, binding_column, background);
+                }
+            });
+        }
+    });
+}
+
+fn row_columns(
+    row: SettingsRow,
+    settings: &GameSettings,
+    bindings: &InputBindings,
+    loc: &Localization,
+) -> Vec<String> {
+    let value = |value: String| format!("< {} >", value);
+    let percent = |fraction: f32| value(format!("{:.0}%", fraction * 100.0));
+    let (label, value) = match row {
+        SettingsRow::MasterVolume => ("settings.master_volume", percent(settings.master_volume)),
+        SettingsRow::MusicVolume => ("settings.music_volume", percent(settings.music_volume)),
+        SettingsRow::SfxVolume => ("settings.sfx_volume", percent(settings.sfx_volume)),
+        SettingsRow::TextSpeed => ("settings.text_speed", value(loc.t(settings.text_speed.name_key()))),
+        SettingsRow::CombatSpeed => ("settings.combat_speed", value(format!("{}x", settings.combat_speed))),
+        SettingsRow::WindowMode => ("settings.window_mode", value(loc.t(settings.window_mode.name_key()))),
+        SettingsRow::Vsync => (
+            "settings.vsync",
+            value(loc.t(if settings.vsync { "settings.on" } else { "settings.off" })),
+        ),
+        SettingsRow::UiScale => ("settings.ui_scale", percent(settings.ui_scale)),
+        SettingsRow::Language => ("settings.language", value(settings.language.name().to_string())),
+        SettingsRow::Back => return vec![loc.t("settings.back")],
+        SettingsRow::Binding(action) => {
+            return vec![
+                action.name(loc),
+                binding_labels(bindings, action, InputDevice::Keyboard, loc),
+                binding_labels(bindings, action, InputDevice::Gamepad, loc),
+            ];
+        }
+        SettingsRow::ResetBindings => return vec![loc.t("settings.reset_bindings")],
+    };
+    vec![loc.t(label), value]
+}
+
+fn binding_labels(bindings: &InputBindings, action: GameAction, device: InputDevice, loc: &Localization) -> String {
+    let labels: Vec<String> = bindings.get(action)
+        .iter()
+        .filter(|binding| binding.device() == device)
+        .map(|binding| binding.label(loc))
+        .collect();
+    if labels.is_empty() {
+        "-".to_string()
+    } else {
+        labels.join(", ")
+    }
+}
+
+fn spawn_settings_row(
+    parent: &mut ChildSpawnerCommands<'_>,
+    font: &Handle<Font>,
+    index: usize,
+    columns: Vec<String>,
+    binding_column: bool,
+    background: Color,
+) {
+    parent.spawn((
+        Button,
+        Node {
+            width: Val::Px(if binding_column { 620.0 } else { 420.0 }),
+            flex_direction: FlexDirection::Row,
+            padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(background),
+        SettingsRowButton(index),
+    ))
+    .with_children(|row| {
+        for (column, label) in columns.into_iter().enumerate() {
+            row.spawn((
+                Text::new(label),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                Node {
+                    width: Val::Px(match column {
+                        0 if binding_column => 260.0,
+                        0 => 230.0,
+                        _ => 170.0,
+                    }),
+                    ..default()
+                },
+            ));
+        }
+    });
+}
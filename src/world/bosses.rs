@@ -0,0 +1,114 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+use super::data::EnemyTypeData;
+use super::character_configs::EnemyConfigs;
+
+// How a boss chooses finte/wuchtschlag levels
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum BossAi {
+    Random,     // same as normal enemies
+    Aggressive, // at least half of its levels
+    AllOut,     // always max levels
+}
+
+// Phase that starts once the boss drops to hp_percent of its max HP
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossPhase {
+    pub hp_percent: u32,
+    #[serde(default)]
+    pub attack_bonus: u32,
+    #[serde(default)]
+    pub damage_bonus: u32,
+    #[serde(default)]
+    pub armor_bonus: u32,
+    pub ai: BossAi,
+    pub message: String, // localization key
+}
+
+// Special attack, announced one turn before it hits
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossSpecialAttack {
+    pub name: String, // localization key
+    pub every_n_turns: u32,
+    #[serde(default)]
+    pub damage_bonus: u32,
+    #[serde(default)]
+    pub dice_bonus: u32,
+    pub announcement: String, // localization key
+}
+
+// Minions that join the fight once the boss drops to hp_percent
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossSummon {
+    pub hp_percent: u32,
+    pub enemy: EnemyTypeData,
+    pub count: u32,
+    pub message: String, // localization key
+}
+
+// Phases and summons, sorted by falling hp_percent when loaded
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossScript {
+    #[serde(default)]
+    pub phases: Vec<BossPhase>,
+    #[serde(default)]
+    pub special_attack: Option<BossSpecialAttack>,
+    #[serde(default)]
+    pub summons: Vec<BossSummon>,
+}
+
+// Boss scripts per enemy type (boss field in enemies.ron), enemies without one fight normally
+#[derive(Resource, Clone, Debug, Default)]
+pub struct BossScripts {
+    scripts: HashMap<EnemyTypeData, BossScript>,
+}
+
+impl BossScripts {
+    pub fn from_enemies(enemies: &EnemyConfigs) -> Self {
+        let mut scripts = HashMap::new();
+        for (&enemy, config) in &enemies.0 {
+            if let Some(script) = &config.boss {
+                let mut script = script.clone();
+                // the fight walks them front to back, so the highest threshold comes first
+                script.phases.sort_by_key(|phase| Reverse(phase.hp_percent));
+                script.summons.sort_by_key(|summon| Reverse(summon.hp_percent));
+                scripts.insert(enemy, script);
+            }
+        }
+        Self { scripts }
+    }
+
+    pub fn get(&self, enemy: EnemyTypeData) -> Option<&BossScript> {
+        self.scripts.get(&enemy)
+    }
+
+    pub fn is_boss(&self, enemy: EnemyTypeData) -> bool {
+        self.scripts.contains_key(&enemy)
+    }
+}
+
+// How far the fight has progressed through the boss script (BossScripts resource)
+#[derive(Component)]
+pub struct BossBehavior {
+    pub enemy: EnemyTypeData,
+    pub ai: BossAi,
+    pub phases_reached: usize,
+    pub summons_done: usize,
+    pub turns_taken: u32,
+    pub charging_special: bool, // special attack was announced, hits next turn
+}
+
+impl BossBehavior {
+    pub fn new(enemy: EnemyTypeData) -> Self {
+        Self {
+            enemy,
+            ai: BossAi::Random,
+            phases_reached: 0,
+            summons_done: 0,
+            turns_taken: 0,
+            charging_special: false,
+        }
+    }
+}
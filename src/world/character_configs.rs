@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use serde::Deserialize;
+use crate::config::Config;
+use super::data::{EnemyTypeData, PlayerTypeData};
+use super::bosses::BossScript;
+use super::sprites::SpriteConfig;
+
+// One entry in enemies.ron, the stat fields are only there for reference (see EnemyTypeData::stats)
+#[derive(Clone, Debug, Deserialize)]
+pub struct EnemyConfig {
+    #[serde(default)]
+    pub threat: u32, // 0: computed from the stats
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub sprite: Option<SpriteConfig>,
+    #[serde(default)]
+    pub boss: Option<BossScript>,
+}
+
+// All of enemies.ron, read once for the encounter table, boss scripts and sprites
+#[derive(Clone, Debug, Default)]
+pub struct EnemyConfigs(pub HashMap<EnemyTypeData, EnemyConfig>);
+
+impl Config for EnemyConfigs {
+    fn from_ron(content: &str) -> Result<Self, String> {
+        let configs = ron::from_str::<HashMap<String, EnemyConfig>>(content).map_err(|err| err.to_string())?;
+        let mut enemies = HashMap::new();
+        for (id, config) in configs {
+            let enemy = EnemyTypeData::ALL.into_iter()
+                .find(|enemy| enemy.id() == id)
+                .ok_or(format!("unknown enemy {}", id))?;
+            enemies.insert(enemy, config);
+        }
+        Ok(Self(enemies))
+    }
+}
+
+// One entry in classes.ron
+#[derive(Clone, Debug, Deserialize)]
+pub struct ClassConfig {
+    #[serde(default)]
+    pub sprite: Option<SpriteConfig>,
+}
+
+// All of classes.ron
+#[derive(Clone, Debug, Default)]
+pub struct ClassConfigs(pub HashMap<PlayerTypeData, ClassConfig>);
+
+impl Config for ClassConfigs {
+    fn from_ron(content: &str) -> Result<Self, String> {
+        let configs = ron::from_str::<HashMap<String, ClassConfig>>(content).map_err(|err| err.to_string())?;
+        let mut classes = HashMap::new();
+        for (id, config) in configs {
+            let player = PlayerTypeData::ALL.into_iter()
+                .find(|player| player.id() == id)
+                .ok_or(format!("unknown class {}", id))?;
+            classes.insert(player, config);
+        }
+        Ok(Self(classes))
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
+use crate::character::EnemyBundle;
+use crate::localization::Localization;
+use serde::Deserialize;
 
 // enemytypes (wie EnemyType enum in java projekt)
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
 pub enum EnemyTypeData {
     MiniZombie,
     Scientist,
@@ -11,18 +14,75 @@ pub enum EnemyTypeData {
 
 impl EnemyTypeData {
     pub fn stats(&self) -> (u32, u32, u32, u32, u32, u32, u32, u32, u32, &'static str) {
-        // life, armor, initiative, attack, defense, damage, numW6, finte, wuchtschlag, name
+        // life, armor, initiative, attack, defense, damage, numW6, finte, wuchtschlag, name key
         match self {
-            Self::MiniZombie => (10, 1, 12, 10, 6, 1, 1, 1, 0, "Mini Zombie"),
-            Self::Scientist => (15, 0, 10, 12, 10, 4, 1, 2, 1, "Scientist"),
-            Self::BigZombie => (20, 3, 6, 13, 8, 2, 2, 1, 2, "Big Zombie"),
-            Self::Endboss => (50, 5, 4, 14, 10, 1, 4, 3, 3, "Endboss"),
+            Self::MiniZombie => (10, 1, 12, 10, 6, 1, 1, 1, 0, "enemy.mini_zombie"),
+            Self::Scientist => (15, 0, 10, 12, 10, 4, 1, 2, 1, "enemy.scientist"),
+            Self::BigZombie => (20, 3, 6, 13, 8, 2, 2, 1, 2, "enemy.big_zombie"),
+            Self::Endboss => (50, 5, 4, 14, 10, 1, 4, 3, 3, "enemy.endboss"),
+        }
+    }
+
+    pub const ALL: [Self; 4] = [Self::MiniZombie, Self::Scientist, Self::BigZombie, Self::Endboss];
+
+    // Key in enemies.ron
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::MiniZombie => "MiniZombie",
+            Self::Scientist => "Scientist",
+            Self::BigZombie => "BigZombie",
+            Self::Endboss => "Endboss",
+        }
+    }
+
+    // Threat from the stats: average hit (scaled by attack) times how much it takes
+    // to bring the enemy down (scaled by defense), square root to keep it additive.
+    // Can be overridden with the threat field in enemies.ron.
+    pub fn computed_threat(&self) -> u32 {
+        let (life, armor, _, attack, defense, damage, num_w6, _, _, _) = self.stats();
+        let offense = (damage as f32 + 3.5 * num_w6 as f32) * attack as f32 / 10.0;
+        let durability = (life + 2 * armor) as f32 * defense as f32 / 8.0;
+        (offense * durability).sqrt().round().max(1.0) as u32
+    }
+
+    pub fn bundle(&self, loc: &Localization) -> EnemyBundle {
+        let stats = self.stats();
+        EnemyBundle::new(
+            loc.t(stats.9), stats.0, stats.1, stats.2,
+            stats.3, stats.4, stats.5, stats.6, stats.7, stats.8
+        )
+    }
+}
+
+// playertypes (wie PlayerType enum in java projekt)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PlayerTypeData {
+    SwordFighter,
+    ShieldFighter,
+}
+
+impl PlayerTypeData {
+    pub fn stats(&self) -> (u32, u32, u32, u32, u32, u32, u32, u32, u32, &'static str) {
+        // life, armor, initiative, attack, defense, damage, numW6, finte, wuchtschlag, name key
+        match self {
+            Self::SwordFighter => (20, 1, 14, 12, 10, 3, 2, 1, 1, "character.sword_fighter"),
+            Self::ShieldFighter => (25, 3, 10, 10, 12, 2, 2, 1, 0, "character.shield_fighter"),
+        }
+    }
+
+    pub const ALL: [Self; 2] = [Self::SwordFighter, Self::ShieldFighter];
+
+    // Key in classes.ron
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::SwordFighter => "SwordFighter",
+            Self::ShieldFighter => "ShieldFighter",
         }
     }
 }
 
 // roomtypes (wie RoomType enum in java projekt)
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
 pub enum RoomTypeData {
     IntroRoom,
     FloorRoom,
@@ -32,6 +92,8 @@ pub enum RoomTypeData {
     Laboratory,
     Corridor,
     FinalRoom,
+    RestRoom,     // no fight, only in generated dungeons
+    TreasureRoom, // no fight, a random upgrade
 }
 
 impl RoomTypeData {
@@ -46,25 +108,96 @@ impl RoomTypeData {
             Self::Laboratory => vec![Scientist, Scientist, Scientist, Scientist],
             Self::Corridor => vec![BigZombie, BigZombie, BigZombie],
             Self::FinalRoom => vec![Endboss],
+            Self::RestRoom | Self::TreasureRoom => vec![],
         }
     }
     
-    pub fn name(&self) -> &'static str {
+    pub fn name_key(&self) -> &'static str {
         match self {
-            Self::IntroRoom => "Intro Room",
-            Self::FloorRoom => "Floor Room",
-            Self::Pantry1 => "Pantry",
-            Self::LibraryRoom => "Library",
-            Self::DiningHall => "Dining Hall",
-            Self::Laboratory => "Laboratory",
-            Self::Corridor => "Corridor",
-            Self::FinalRoom => "Final Room",
+            Self::IntroRoom => "room.intro.name",
+            Self::FloorRoom => "room.floor.name",
+            Self::Pantry1 => "room.pantry.name",
+            Self::LibraryRoom => "room.library.name",
+            Self::DiningHall => "room.dining_hall.name",
+            Self::Laboratory => "room.laboratory.name",
+            Self::Corridor => "room.corridor.name",
+            Self::FinalRoom => "room.final.name",
+            Self::RestRoom => "room.rest.name",
+            Self::TreasureRoom => "room.treasure.name",
+        }
+    }
+
+    // Story shown when entering the room
+    pub fn story_key(&self) -> &'static str {
+        match self {
+            Self::IntroRoom => "room.intro.story",
+            Self::FloorRoom => "room.floor.story",
+            Self::Pantry1 => "room.pantry.story",
+            Self::LibraryRoom => "room.library.story",
+            Self::DiningHall => "room.dining_hall.story",
+            Self::Laboratory => "room.laboratory.story",
+            Self::Corridor => "room.corridor.story",
+            Self::FinalRoom => "room.final.story",
+            Self::RestRoom => "room.rest.story",
+            Self::TreasureRoom => "room.treasure.story",
+        }
+    }
+
+    // Enemy tags a generated room of this type draws from
+    pub fn encounter_tags(&self) -> &'static [&'static str] {
+        match self {
+            Self::IntroRoom | Self::FloorRoom | Self::Pantry1 => &["small"],
+            Self::LibraryRoom | Self::DiningHall => &["small", "lab"],
+            Self::Laboratory => &["lab", "brute"],
+            Self::Corridor => &["brute"],
+            Self::FinalRoom => &["boss"],
+            Self::RestRoom | Self::TreasureRoom => &[],
+        }
+    }
+
+    // Door hint pointing into this room, used by generated dungeons
+    pub fn door_hint_key(&self) -> &'static str {
+        match self {
+            Self::IntroRoom => "door.hint.intro",
+            Self::FloorRoom => "door.hint.floor",
+            Self::Pantry1 => "door.hint.pantry",
+            Self::LibraryRoom => "door.hint.library",
+            Self::DiningHall => "door.hint.dining_hall",
+            Self::Laboratory => "door.hint.laboratory",
+            Self::Corridor => "door.hint.corridor",
+            Self::FinalRoom => "door.hint.final",
+            Self::RestRoom => "door.hint.rest",
+            Self::TreasureRoom => "door.hint.treasure",
         }
     }
 }
 
+// Enemies that join the fight once after_round is over (reinforcements field in rooms.ron)
+#[derive(Clone, Debug, Deserialize)]
+pub struct Reinforcements {
+    pub after_round: u32,
+    pub enemies: Vec<EnemyTypeData>,
+    pub message: String, // localization key
+}
+
+// A door out of a room (exits field in rooms.ron)
+#[derive(Clone, Debug)]
+pub struct RoomExit {
+    pub to: usize, // index into RoomGraph::rooms
+    pub hint: String, // localization key, e.g. "door.intro.left"
+}
+
+// escape rules per room (flee field in rooms.ron), fleeing has to be allowed explicitly
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum FleeRule {
+    #[default]
+    Forbidden,
+    ToPrevious, // back to the room before
+    ToNext,     // alternate exit, skips the reward
+}
+
 // upgradetypes (wie UpgradeType enum in java projekt)
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum UpgradeTypeData {
     Finte,
     Life,
@@ -76,6 +209,11 @@ pub enum UpgradeTypeData {
 }
 
 impl UpgradeTypeData {
+    pub const ALL: [Self; 7] = [
+        Self::Finte, Self::Life, Self::Damage, Self::Skill, Self::Armour, Self::Attack, Self::PantryCompound,
+    ];
+
+    // Reward of the fixed rooms, treasure rooms roll one from ALL
     pub fn for_room(room: RoomTypeData) -> Option<Self> {
         match room {
             RoomTypeData::IntroRoom => Some(Self::Life),
@@ -85,7 +223,20 @@ impl UpgradeTypeData {
             RoomTypeData::DiningHall => Some(Self::Armour),
             RoomTypeData::Laboratory => Some(Self::Attack),
             RoomTypeData::Corridor => Some(Self::Finte),
-            RoomTypeData::FinalRoom => None,
+            RoomTypeData::FinalRoom | RoomTypeData::RestRoom | RoomTypeData::TreasureRoom => None,
+        }
+    }
+    
+    // Text shown after the room was won
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            Self::Life => "upgrade.life",
+            Self::Damage => "upgrade.damage",
+            Self::Finte => "upgrade.finte",
+            Self::Skill => "upgrade.skill",
+            Self::Armour => "upgrade.armour",
+            Self::Attack => "upgrade.attack",
+            Self::PantryCompound => "upgrade.pantry_compound",
         }
     }
     
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use crate::character::EnemyBundle;
+use crate::localization::Localization;
+use super::data::UpgradeTypeData;
+
+// Difficulty of a run, picked on the character screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+// Changes to enemy stats when they spawn
+pub struct EnemyModifiers {
+    pub health: f32, // multiplier
+    pub attack: i32, // offset
+    pub damage: f32, // multiplier
+    pub dice: i32,   // offset to the number of w6
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 4] = [Self::Easy, Self::Normal, Self::Hard, Self::Nightmare];
+
+    pub fn name_key(&self) -> &'static str {
+        match self {
+            Self::Easy => "difficulty.easy",
+            Self::Normal => "difficulty.normal",
+            Self::Hard => "difficulty.hard",
+            Self::Nightmare => "difficulty.nightmare",
+        }
+    }
+
+    // Left/Right on the character screen, wraps around
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn enemy_modifiers(&self) -> EnemyModifiers {
+        match self {
+            Self::Easy => EnemyModifiers { health: 0.75, attack: -1, damage: 0.75, dice: 0 },
+            Self::Normal => EnemyModifiers { health: 1.0, attack: 0, damage: 1.0, dice: 0 },
+            Self::Hard => EnemyModifiers { health: 1.25, attack: 1, damage: 1.25, dice: 0 },
+            Self::Nightmare => EnemyModifiers { health: 1.5, attack: 2, damage: 1.5, dice: 1 },
+        }
+    }
+
+    // Scales the stat points of upgrades, levels (finte, wuchtschlag) stay as they are
+    pub fn reward_multiplier(&self) -> f32 {
+        match self {
+            Self::Easy => 1.5,
+            Self::Normal => 1.0,
+            Self::Hard => 0.75,
+            Self::Nightmare => 0.5,
+        }
+    }
+
+    pub fn apply_to_enemy(&self, enemy: &mut EnemyBundle) {
+        let modifiers = self.enemy_modifiers();
+        let health = ((enemy.health.max as f32 * modifiers.health).round() as u32).max(1);
+        enemy.health.max = health;
+        enemy.health.current = health;
+        enemy.attack.0 = enemy.attack.0.saturating_add_signed(modifiers.attack).max(1);
+        enemy.damage.0 = (enemy.damage.0 as f32 * modifiers.damage).round() as u32;
+        enemy.dice.0 = enemy.dice.0.saturating_add_signed(modifiers.dice).max(1);
+    }
+
+    // Upgrade stats as in UpgradeTypeData::stats, with the reward multiplier applied
+    pub fn upgrade_stats(&self, upgrade: UpgradeTypeData) -> (u32, u32, u32, u32, u32, u32, u32, u32, u32) {
+        let stats = upgrade.stats();
+        let scale = |value: u32| {
+            if value == 0 {
+                0
+            } else {
+                ((value as f32 * self.reward_multiplier()).round() as u32).max(1)
+            }
+        };
+        (
+            scale(stats.0), scale(stats.1), scale(stats.2), scale(stats.3),
+            scale(stats.4), scale(stats.5), scale(stats.6), stats.7, stats.8,
+        )
+    }
+
+    // Reward text with the scaled numbers
+    pub fn upgrade_text(&self, upgrade: UpgradeTypeData, loc: &Localization) -> String {
+        let stats = self.upgrade_stats(upgrade);
+        loc.t_with(upgrade.text_key(), &[
+            ("life", &stats.1),
+            ("armor", &stats.2),
+            ("attack", &stats.4),
+            ("damage", &stats.6),
+        ])
+    }
+}
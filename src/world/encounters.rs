@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use super::data::EnemyTypeData;
+use super::character_configs::EnemyConfigs;
+
+// Groups never get bigger than this, whatever the budget
+pub const MAX_ENEMIES: usize = 5;
+
+// Threat per enemy type, computed from the stats unless enemies.ron overrides it.
+// Tags only come from enemies.ron, without them budget rooms find no enemies.
+#[derive(Resource, Clone, Debug)]
+pub struct EncounterTable {
+    threats: HashMap<EnemyTypeData, u32>,
+    tags: HashMap<EnemyTypeData, Vec<String>>,
+}
+
+impl Default for EncounterTable {
+    fn default() -> Self {
+        Self {
+            threats: EnemyTypeData::ALL.iter().map(|&enemy| (enemy, enemy.computed_threat())).collect(),
+            tags: HashMap::new(),
+        }
+    }
+}
+
+impl EncounterTable {
+    // Tags and threat overrides from enemies.ron, enemies without threat keep the computed one
+    pub fn from_enemies(enemies: &EnemyConfigs) -> Self {
+        let mut table = Self::default();
+        for (&enemy, config) in &enemies.0 {
+            if config.threat > 0 {
+                table.threats.insert(enemy, config.threat);
+            }
+            if !config.tags.is_empty() {
+                table.tags.insert(enemy, config.tags.clone());
+            }
+        }
+        table
+    }
+
+    pub fn threat(&self, enemy: EnemyTypeData) -> u32 {
+        self.threats.get(&enemy).copied().unwrap_or_else(|| enemy.computed_threat())
+    }
+
+    pub fn has_any_tag(&self, enemy: EnemyTypeData, tags: &[String]) -> bool {
+        self.tags.get(&enemy).is_some_and(|own| own.iter().any(|tag| tags.contains(tag)))
+    }
+
+    // Total threat of a group, e.g. to compare a room against its budget
+    pub fn encounter_threat(&self, enemies: &[EnemyTypeData]) -> u32 {
+        enemies.iter().map(|&enemy| self.threat(enemy)).sum()
+    }
+}
+
+// A room that rolls its enemies instead of listing them (budget and tags fields in rooms.ron)
+#[derive(Clone, Debug)]
+pub struct EncounterBudget {
+    pub budget: u32,
+    pub tags: Vec<String>,
+}
+
+impl EncounterBudget {
+    pub fn new(budget: u32, tags: &[&str]) -> Self {
+        Self {
+            budget,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+}
+
+// Random group of tagged enemies whose threat fits the budget.
+// If not even the weakest one fits, the weakest one comes alone.
+pub fn pick_encounter(budget: &EncounterBudget, table: &EncounterTable, rng: &mut impl Rng) -> Vec<EnemyTypeData> {
+    let pool: Vec<EnemyTypeData> = EnemyTypeData::ALL.into_iter()
+        .filter(|&enemy| table.has_any_tag(enemy, &budget.tags))
+        .collect();
+
+    let mut enemies = Vec::new();
+    let mut remaining = budget.budget;
+    while enemies.len() < MAX_ENEMIES {
+        let affordable: Vec<EnemyTypeData> = pool.iter()
+            .copied()
+            .filter(|&enemy| table.threat(enemy) <= remaining)
+            .collect();
+        let Some(&enemy) = affordable.choose(rng) else {
+            break;
+        };
+        remaining -= table.threat(enemy);
+        enemies.push(enemy);
+    }
+    if enemies.is_empty() {
+        enemies.extend(pool.iter().copied().min_by_key(|&enemy| table.threat(enemy)));
+    }
+    enemies
+}
@@ -1,106 +1,492 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::Rng;
 use crate::character::*;
 use crate::game_state::GameState;
 use crate::combat::*;
-use crate::ui::StoryText;
+use crate::config::load_config;
+use crate::input::{ActionPrompts, ActionState, GameAction};
+use crate::localization::Localization;
+use crate::ui::{DoorButton, StoryText};
 use super::data::*;
 use super::resources::*;
+use super::generator::generate_dungeon;
+use super::encounters::EncounterTable;
+use super::bosses::{BossAi, BossBehavior, BossScripts};
+use super::character_configs::{ClassConfigs, EnemyConfigs};
+use super::sprites::SpriteSheets;
+use super::difficulty::Difficulty;
+use super::save::write_save_game;
+use super::run_stats::RunStats;
+use super::rest::{CampOutcome, RestRules};
 
-// System that spawns the player when entering gameplay
+// System that spawns the player once at the start of a run
 pub fn spawn_player_system(
     mut commands: Commands,
     char_selection: Res<crate::input::CharacterSelection>,
+    sprites: Res<SpriteSheets>,
+    loc: Res<Localization>,
 ) {
     // Get player stats based on selection
-    let (life, armor, init, attack, defense, damage, dice, finte, wucht) = 
-        match char_selection.current {
-            0 => (20, 1, 14, 12, 10, 3, 2, 1, 1), // Schwertkrieger
-            1 => (25, 3, 10, 10, 12, 2, 2, 1, 0), // Schildkrieger
-            _ => (20, 1, 14, 12, 10, 3, 2, 1, 1),
-        };
+    let player_type = char_selection.get_player_type();
+    let stats = player_type.stats();
     
-    commands.spawn(PlayerBundle::new(
-        life, armor, init, attack, defense, damage, dice, finte, wucht
+    commands.spawn((
+        PlayerBundle::new(
+            loc.t("character.player"), stats.0, stats.1, stats.2, stats.3, stats.4, stats.5, stats.6, stats.7, stats.8
+        ),
+        sprites.player(player_type),
     ));
     
-    info!("Player spawned with {} HP", life);
+    info!("Player spawned as {} with {} HP", loc.t(stats.9), stats.0);
+}
+
+// Spawns enemies of the current run with their sprite and boss script, scaled by the difficulty
+#[derive(SystemParam)]
+pub struct EnemySpawner<'w, 's> {
+    commands: Commands<'w, 's>,
+    boss_scripts: Res<'w, BossScripts>,
+    sprites: Res<'w, SpriteSheets>,
+    loc: Res<'w, Localization>,
+}
+
+impl EnemySpawner<'_, '_> {
+    pub fn spawn(&mut self, enemy_type: EnemyTypeData, difficulty: Difficulty) -> Entity {
+        self.spawn_with(enemy_type, difficulty, |_| {})
+    }
+
+    // Joins a running fight, so it rolls its own initiative (w6 + base)
+    pub fn spawn_mid_fight(&mut self, enemy_type: EnemyTypeData, difficulty: Difficulty) -> Entity {
+        self.spawn_with(enemy_type, difficulty, |enemy| {
+            enemy.initiative.randomized = enemy.initiative.base + rand::rng().random_range(1..=6);
+        })
+    }
+
+    fn spawn_with(&mut self, enemy_type: EnemyTypeData, difficulty: Difficulty, setup: impl FnOnce(&mut EnemyBundle)) -> Entity {
+        let mut bundle = enemy_type.bundle(&self.loc);
+        difficulty.apply_to_enemy(&mut bundle);
+        setup(&mut bundle);
+        let mut enemy = self.commands.spawn((bundle, self.sprites.enemy(enemy_type)));
+        if self.boss_scripts.is_boss(enemy_type) {
+            enemy.insert(BossBehavior::new(enemy_type));
+        }
+        enemy.id()
+    }
+}
+
+// System that loads the room graph config, keeps the built-in layout if it is missing
+pub fn load_room_graph_system(mut graph: ResMut<RoomGraph>) {
+    if let Some(loaded) = load_config("assets/config/rooms.ron") {
+        *graph = loaded;
+    }
+}
+
+// System that reads enemies.ron once for the encounter table, boss scripts and sprites.
+// Without it threats are computed, no enemy has tags or a boss script and all are drawn as rectangles
+pub fn load_enemy_configs_system(
+    mut table: ResMut<EncounterTable>,
+    mut scripts: ResMut<BossScripts>,
+    mut sprites: ResMut<SpriteSheets>,
+) {
+    let Some(enemies) = load_config::<EnemyConfigs>("assets/config/enemies.ron") else {
+        return;
+    };
+    *table = EncounterTable::from_enemies(&enemies);
+    *scripts = BossScripts::from_enemies(&enemies);
+    sprites.load_enemies(&enemies);
+}
+
+// System that loads the class sprites, classes missing from the file are drawn as the fallback rectangle
+pub fn load_class_configs_system(mut sprites: ResMut<SpriteSheets>) {
+    if let Some(classes) = load_config::<ClassConfigs>("assets/config/classes.ron") {
+        sprites.load_classes(&classes);
+    }
+}
+
+// System that loads the healing rules, keeps the defaults if the file is missing
+pub fn load_rest_rules_system(mut rules: ResMut<RestRules>) {
+    if let Some(loaded) = load_config("assets/config/rest.ron") {
+        *rules = loaded;
+    }
+}
+
+// System that sets up the dungeon when a new run starts
+pub fn start_run_system(
+    graph: Res<RoomGraph>,
+    encounters: Res<EncounterTable>,
+    run_setup: Res<RunSetup>,
+    rest_rules: Res<RestRules>,
+    mut world: ResMut<WorldState>,
+    mut stats: ResMut<RunStats>,
+) {
+    *world = new_run(&graph, &encounters, &run_setup);
+    world.camps_left = rest_rules.camp_uses;
+    *stats = RunStats::default();
+    write_save_game(&world);
+}
+
+// Start over with the same setup, e.g. "Neustart" on the end screens
+pub fn restart_run(commands: &mut Commands) {
+    commands.run_system_cached(super::cleanup_run_system);
+    commands.run_system_cached(start_run_system);
+    commands.run_system_cached(spawn_player_system);
+}
+
+// Fight the room again after a defeat with the HP from the start of the fight, upgrades are kept
+pub fn retry_room_system(world: Res<WorldState>, mut player_query: Query<&mut Health, With<Player>>) {
+    for mut health in player_query.iter_mut() {
+        health.current = world.room_start_health.unwrap_or(health.max).min(health.max);
+    }
+}
+
+// Fresh dungeon for the run setup
+pub fn new_run(graph: &RoomGraph, encounters: &EncounterTable, run_setup: &RunSetup) -> WorldState {
+    let mut world = match run_setup.seed {
+        Some(seed) => {
+            info!("Generating dungeon from seed {}", seed);
+            generate_dungeon(seed, encounters)
+        }
+        None => {
+            let mut graph = graph.clone();
+            graph.roll_encounters(encounters, &mut rand::rng());
+            WorldState::from_graph(graph)
+        }
+    };
+    world.difficulty = run_setup.difficulty;
+    world.permadeath = run_setup.permadeath;
+    world
 }
 
 // System that loads a room and displays its story
 pub fn load_room_system(
     mut story_text: ResMut<StoryText>,
     world: Res<WorldState>,
+    loc: Res<Localization>,
 ) {
+    // After fleeing the flee text stays until the player goes on (continue_after_flee_system)
+    if world.fled {
+        return;
+    }
+    // After a victory the story shows the doors until the player picks one
+    if world.door_choice.is_some() {
+        return;
+    }
     let room = world.current_room();
     
-    // Set story text based on room
-    let story = match room {
-        RoomTypeData::IntroRoom => {
-            "Du erwachst in einem dunklen Raum. Die Luft ist feucht und riecht nach Verwesung. \
-             Ein untotes Wesen kriecht auf dich zu..."
-        }
-        RoomTypeData::FloorRoom => {
-            "Der Boden ist übersät mit Blut und Knochen. Zwei Zombies blockieren deinen Weg."
-        }
-        RoomTypeData::Pantry1 => {
-            "Du betrittst eine alte Vorratskammer. Verweste Lebensmittel liegen überall herum. \
-             Doch hier lauern auch Gefahren..."
-        }
-        RoomTypeData::LibraryRoom => {
-            "Eine große Bibliothek voller verstaubter Bücher. Doch nicht alle hier sind tot..."
-        }
-        RoomTypeData::DiningHall => {
-            "Der Speisesaal war einst prächtig. Jetzt ist er voller Untoten, die auf ihr nächstes Mahl warten."
-        }
-        RoomTypeData::Laboratory => {
-            "Ein Labor voller mysteriöser Geräte. Die Wissenschaftler arbeiten noch immer... \
-             oder das, was von ihnen übrig ist."
-        }
-        RoomTypeData::Corridor => {
-            "Ein langer Korridor. Am Ende siehst du massive Gestalten auf dich zukommen."
-        }
-        RoomTypeData::FinalRoom => {
-            "Der finale Raum. Hier lauert der Meister aller Untoten. Dies ist dein letzter Kampf!"
+    story_text.set(loc.t(room.story_key()));
+    
+    info!("Loaded room: {}", loc.t(room.name_key()));
+}
+
+// System that shows the story of the room the player fled into once the flee text is confirmed
+pub fn continue_after_flee_system(
+    actions: Res<ActionState>,
+    mut story_text: ResMut<StoryText>,
+    mut world: ResMut<WorldState>,
+    loc: Res<Localization>,
+) {
+    if !world.fled || !story_text.is_finished() || !actions.just_pressed(GameAction::Confirm) {
+        return;
+    }
+    world.fled = false;
+    story_text.set(loc.t(world.current_room().story_key()));
+}
+
+// System that lets the player pick the next room after a victory
+pub fn choose_door_system(
+    actions: Res<ActionState>,
+    mut story_text: ResMut<StoryText>,
+    mut world: ResMut<WorldState>,
+    loc: Res<Localization>,
+    door_buttons: Query<(&Interaction, &DoorButton), Changed<Interaction>>,
+) {
+    let Some(selected) = world.door_choice else {
+        return;
+    };
+    let door_count = world.exits().len();
+    
+    let clicked = door_buttons.iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button.0);
+    let number = actions.selected_number()
+        .map(usize::from)
+        .filter(|&n| n >= 1 && n <= door_count)
+        .map(|n| n - 1);
+    // Confirm first reveals the rest of the victory text
+    let confirmed = (story_text.is_finished() && actions.just_pressed(GameAction::Confirm)).then_some(selected);
+    
+    if let Some(index) = clicked.or(number).or(confirmed) {
+        if world.enter(index) {
+            let room = world.current_room();
+            story_text.set(loc.t(room.story_key()));
+            info!("Entered room: {} (depth {})", loc.t(room.name_key()), world.depth());
         }
+    } else if actions.just_pressed(GameAction::Up) && selected > 0 {
+        world.door_choice = Some(selected - 1);
+    } else if actions.just_pressed(GameAction::Down) && selected + 1 < door_count {
+        world.door_choice = Some(selected + 1);
+    }
+}
+
+// System that lets the player camp between rooms, while the doors are shown
+pub fn camp_system(
+    actions: Res<ActionState>,
+    rules: Res<RestRules>,
+    mut story_text: ResMut<StoryText>,
+    mut world: ResMut<WorldState>,
+    loc: Res<Localization>,
+    mut player_query: Query<&mut Health, With<Player>>,
+) {
+    if world.door_choice.is_none() || !actions.just_pressed(GameAction::Camp) {
+        return;
+    }
+    let Ok(mut health) = player_query.single_mut() else {
+        return;
     };
     
-    story_text.full_text = story.to_string();
-    story_text.visible_chars = 0;
-    story_text.timer.reset();
+    let mut camps_left = world.camps_left;
+    let outcome = rules.camp(&mut camps_left, &mut health, &mut rand::rng());
+    world.camps_left = camps_left;
+    let text = match outcome {
+        CampOutcome::Rested { healed } => loc.t_with("story.camped", &[("healed", &healed), ("left", &camps_left)]),
+        CampOutcome::Ambushed { damage } => loc.t_with("story.camp_ambushed", &[("damage", &damage), ("left", &camps_left)]),
+        CampOutcome::NoCampsLeft => loc.t("story.no_camps_left"),
+    };
+    story_text.set(text);
+    info!("Camp: {:?}", outcome);
+}
+
+// System for rooms without enemies (rest and treasure rooms): take the reward or rest and move on
+pub fn clear_peaceful_room_system(
+    actions: Res<ActionState>,
+    rest_rules: Res<RestRules>,
+    mut story_text: ResMut<StoryText>,
+    mut world: ResMut<WorldState>,
+    loc: Res<Localization>,
+    mut stats: ResMut<RunStats>,
+    mut player_query: PlayerUpgradeQuery,
+) {
+    if world.door_choice.is_some() || world.fled || !world.current().enemies.is_empty() || world.is_cleared() {
+        return;
+    }
+    if !story_text.is_finished() || !actions.just_pressed(GameAction::Confirm) {
+        return;
+    }
     
-    info!("Loaded room: {}", room.name());
+    let difficulty = world.difficulty;
+    let text = match world.current().upgrade {
+        Some(upgrade) => {
+            apply_upgrade(upgrade, difficulty, &mut player_query);
+            stats.upgrades_taken += 1;
+            loc.t_with("story.treasure_taken", &[("upgrade", &difficulty.upgrade_text(upgrade, &loc))])
+        }
+        None => {
+            let healed = player_query.single_mut()
+                .map(|(mut health, ..)| rest_rules.rest(&mut health))
+                .unwrap_or(0);
+            loc.t_with("story.rested", &[("healed", &healed)])
+        }
+    };
+    story_text.set(text);
+    stats.rooms_cleared += 1;
+    world.mark_cleared();
+    if world.has_next_room() {
+        world.door_choice = Some(0);
+    }
 }
 
 // System that starts combat when story is finished
 pub fn start_combat_when_ready_system(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     story_text: Res<StoryText>,
-    mut commands: Commands,
+    mut spawner: EnemySpawner,
     mut next_state: ResMut<NextState<GameState>>,
     mut battle_state: ResMut<BattleState>,
-    world: Res<WorldState>,
+    mut world: ResMut<WorldState>,
+    player_query: Query<&Health, With<Player>>,
 ) {
-    // Wait for story to finish and player to press Enter
-    if story_text.visible_chars >= story_text.full_text.len() 
-        && keyboard.just_pressed(KeyCode::Enter) 
-    {
+    // Wait for story to finish and player to confirm
+    let ready = world.door_choice.is_none() && !world.fled && !world.current().enemies.is_empty() && !world.is_cleared();
+    if ready && story_text.is_finished() && actions.just_pressed(GameAction::Confirm) {
         // Spawn enemies for current room
-        let room = world.current_room();
-        for enemy_type in room.enemies() {
-            let stats = enemy_type.stats();
-            commands.spawn(EnemyBundle::new(
-                stats.9.to_string(), stats.0, stats.1, stats.2, 
-                stats.3, stats.4, stats.5, stats.6, stats.7, stats.8
-            ));
+        for &enemy_type in &world.current().enemies {
+            spawner.spawn(enemy_type, world.difficulty);
         }
         
         // Start combat
+        world.room_start_health = player_query.single().ok().map(|health| health.current);
         battle_state.combat_active = true;
+        battle_state.flee_allowed = world.can_flee();
         next_state.set(GameState::Combat);
         info!("Starting combat!");
     }
 }
 
+// System that brings in room reinforcements once their round is over
+pub fn spawn_reinforcements_system(
+    mut spawner: EnemySpawner,
+    mut round_events: MessageReader<RoundStartEvent>,
+    mut message_events: MessageWriter<CombatMessageEvent>,
+    mut battle_state: ResMut<BattleState>,
+    world: Res<WorldState>,
+    loc: Res<Localization>,
+) {
+    for event in round_events.read() {
+        let Some(reinforcements) = &world.current().reinforcements else {
+            continue;
+        };
+        if event.round_number != reinforcements.after_round + 1 {
+            continue;
+        }
+        
+        for &enemy_type in &reinforcements.enemies {
+            let entity = spawner.spawn_mid_fight(enemy_type, world.difficulty);
+            battle_state.add_combatant(entity);
+        }
+        
+        message_events.write(CombatMessageEvent {
+            message: loc.t(&reinforcements.message),
+            message_type: MessageType::EnemyAction,
+            delay_ms: 500,
+            actor: None,
+            target: None,
+        });
+        info!("Reinforcements arrived in round {}", event.round_number);
+    }
+}
+
+// system that moves bosses through their phases and summons minions
+pub fn boss_script_system(
+    mut spawner: EnemySpawner,
+    mut battle_state: ResMut<BattleState>,
+    mut message_events: MessageWriter<CombatMessageEvent>,
+    mut boss_query: Query<(
+        &mut BossBehavior,
+        &CharacterType,
+        &Health,
+        &mut Attack,
+        &mut Damage,
+        &mut Armor,
+    ), With<Enemy>>,
+    boss_scripts: Res<BossScripts>,
+    world: Res<WorldState>,
+    loc: Res<Localization>,
+) {
+    if !battle_state.combat_active {
+        return;
+    }
+
+    for (mut boss, boss_type, health, mut attack, mut damage, mut armor) in boss_query.iter_mut() {
+        if !health.is_alive() {
+            continue;
+        }
+        let Some(script) = boss_scripts.get(boss.enemy) else {
+            continue;
+        };
+        let hp_percent = health.current * 100 / health.max.max(1);
+
+        // Enter every phase whose threshold has been reached
+        while let Some(phase) = script.phases.get(boss.phases_reached) {
+            if hp_percent > phase.hp_percent {
+                break;
+            }
+            attack.0 += phase.attack_bonus;
+            damage.0 += phase.damage_bonus;
+            armor.0 += phase.armor_bonus;
+            boss.ai = phase.ai;
+            boss.phases_reached += 1;
+
+            message_events.write(CombatMessageEvent {
+                message: loc.t(&phase.message),
+                message_type: MessageType::SpecialMove,
+                delay_ms: 500,
+                actor: Some(boss_type.0.clone()),
+                target: None,
+            });
+        }
+
+        // Summon minions, they join the turn order next round
+        while let Some(summon) = script.summons.get(boss.summons_done) {
+            if hp_percent > summon.hp_percent {
+                break;
+            }
+            for _ in 0..summon.count {
+                let entity = spawner.spawn_mid_fight(summon.enemy, world.difficulty);
+                battle_state.add_combatant(entity);
+            }
+            boss.summons_done += 1;
+
+            message_events.write(CombatMessageEvent {
+                message: loc.t(&summon.message),
+                message_type: MessageType::SpecialMove,
+                delay_ms: 500,
+                actor: Some(boss_type.0.clone()),
+                target: None,
+            });
+        }
+    }
+}
+
+// system that lets bosses fight by their script, on top of the action enemy_ai_system queued
+pub fn boss_turn_system(
+    mut enemy_turn_events: MessageReader<EnemyTurnEvent>,
+    mut message_events: MessageWriter<CombatMessageEvent>,
+    mut boss_query: Query<(&mut BossBehavior, &CharacterType, &SpecialAbilities, &mut QueuedAction), With<Enemy>>,
+    boss_scripts: Res<BossScripts>,
+    loc: Res<Localization>,
+) {
+    for event in enemy_turn_events.read() {
+        let Ok((mut boss, enemy_type, abilities, mut action)) = boss_query.get_mut(event.enemy_entity) else {
+            continue;
+        };
+
+        match boss.ai {
+            BossAi::Random => {}
+            BossAi::Aggressive => {
+                action.finte_level = action.finte_level.max(abilities.finte_level.div_ceil(2));
+                action.wuchtschlag_level = action.wuchtschlag_level.max(abilities.wuchtschlag_level.div_ceil(2));
+            }
+            BossAi::AllOut => {
+                action.finte_level = abilities.finte_level;
+                action.wuchtschlag_level = abilities.wuchtschlag_level;
+            }
+        }
+
+        boss.turns_taken += 1;
+        let special_attack = boss_scripts.get(boss.enemy).and_then(|script| script.special_attack.as_ref());
+        if let Some(special) = special_attack {
+            if boss.charging_special {
+                // Announced last turn, hits now
+                boss.charging_special = false;
+                action.bonus_damage = special.damage_bonus;
+                action.bonus_dice = special.dice_bonus;
+                message_events.write(CombatMessageEvent {
+                    message: loc.t_with("combat.special_unleashed", &[
+                        ("name", &enemy_type.0),
+                        ("attack", &loc.t(&special.name)),
+                    ]),
+                    message_type: MessageType::SpecialMove,
+                    delay_ms: 300,
+                    actor: Some(enemy_type.0.clone()),
+                    target: None,
+                });
+            } else if special.every_n_turns > 0 && (boss.turns_taken + 1) % special.every_n_turns == 0 {
+                // Telegraph the special attack one turn in advance
+                boss.charging_special = true;
+                message_events.write(CombatMessageEvent {
+                    message: loc.t_with("combat.special_announced", &[
+                        ("announcement", &loc.t(&special.announcement)),
+                        ("attack", &loc.t(&special.name)),
+                    ]),
+                    message_type: MessageType::SpecialMove,
+                    delay_ms: 300,
+                    actor: Some(enemy_type.0.clone()),
+                    target: None,
+                });
+            }
+        }
+    }
+}
+
 // System that handles combat end
 pub fn handle_combat_end_system(
     mut commands: Commands,
@@ -108,8 +494,11 @@ pub fn handle_combat_end_system(
     mut next_state: ResMut<NextState<GameState>>,
     mut world: ResMut<WorldState>,
     mut story_text: ResMut<StoryText>,
+    prompts: ActionPrompts,
+    loc: Res<Localization>,
     enemy_query: Query<Entity, With<Enemy>>,
 ) {
+    let confirm = prompts.label(GameAction::Confirm);
     for event in combat_end_events.read() {
         // Despawn all enemies
         for entity in enemy_query.iter() {
@@ -117,80 +506,94 @@ pub fn handle_combat_end_system(
         }
         
         if event.player_won {
-            // Apply room upgrade
-            if let Some(upgrade) = UpgradeTypeData::for_room(world.current_room()) {
-                let upgrade_text = match upgrade {
-                    UpgradeTypeData::Life => "Du findest ein Heilmittel! +5 HP",
-                    UpgradeTypeData::Damage => "Du findest eine bessere Waffe! +2 Schaden",
-                    UpgradeTypeData::Finte => "Du lernst eine neue Finten-Technik!",
-                    UpgradeTypeData::Skill => "Du verbesserst deine Kampffähigkeiten!",
-                    UpgradeTypeData::Armour => "Du findest bessere Rüstung! +3 Rüstung",
-                    UpgradeTypeData::Attack => "Du trainierst deinen Angriff! +2 Angriff",
-                    UpgradeTypeData::PantryCompound => "Du findest eine mächtige Substanz! +5 HP und +2 Schaden",
-                };
-                
-                story_text.full_text = format!(
-                    "Du hast alle Gegner besiegt!\n\n{}\n\nDrücke Enter für den nächsten Raum...",
-                    upgrade_text
-                );
-            } else {
-                story_text.full_text = "Du hast den Endboss besiegt! Du hast gewonnen!\n\nGlückwunsch!".to_string();
-            }
-            
-            story_text.visible_chars = 0;
-            story_text.timer.reset();
-            
-            // Check if there are more rooms
+            world.mark_cleared();
             if world.has_next_room() {
-                world.advance();
+                // Reward text, then the doors to the next rooms
+                let upgrade = world.current().upgrade
+                    .map(|upgrade| world.difficulty.upgrade_text(upgrade, &loc))
+                    .unwrap_or_default();
+                story_text.set(loc.t_with("story.room_won", &[("upgrade", &upgrade)]));
+                world.door_choice = Some(0);
                 next_state.set(GameState::Gameplay);
             } else {
                 // Game won!
+                next_state.set(GameState::Victory);
                 info!("Game completed!");
             }
-        } else {
-            // Player died
-            story_text.full_text = "Du wurdest besiegt...\n\nDrücke ESC für das Hauptmenü".to_string();
-            story_text.visible_chars = 0;
+        } else if event.player_fled {
+            // Player fled, no reward. ToPrevious without a room before takes the side exit too.
+            let flee_text = if world.current().flee == FleeRule::ToPrevious && world.retreat() {
+                loc.t_with("story.fled_back", &[("key", &confirm)])
+            } else if world.enter(0) {
+                // Side exit: the first door of the room
+                loc.t_with("story.fled_forward", &[("key", &confirm)])
+            } else {
+                // can_flee doesn't let this happen, the player stays and can fight again
+                warn!("Fled from {:?} without a room to flee into", world.current_room());
+                story_text.set(loc.t(world.current_room().story_key()));
+                next_state.set(GameState::Gameplay);
+                continue;
+            };
+            
+            // A room that was already won only offers its doors again
+            if world.is_cleared() && world.has_next_room() {
+                world.door_choice = Some(0);
+            } else {
+                world.fled = true;
+            }
+            story_text.set(flee_text);
             next_state.set(GameState::Gameplay);
+        } else {
+            // Player died, with permadeath the game over screen offers no retry
+            next_state.set(GameState::GameOver);
         }
     }
 }
 
-// Apply upgrades after combat
+// Player stats an upgrade can change
+pub type PlayerUpgradeQuery<'w, 's> = Query<'w, 's, (
+    &'static mut Health,
+    &'static mut Armor,
+    &'static mut Attack,
+    &'static mut Defense,
+    &'static mut Damage,
+    &'static mut SpecialAbilities,
+), With<Player>>;
+
+// Apply upgrades after combat (only when the room was won, not when fleeing)
 pub fn apply_upgrades_system(
+    mut combat_end_events: MessageReader<CombatEndEvent>,
     world: Res<WorldState>,
-    mut player_query: Query<(
-        &mut Health,
-        &mut Armor,
-        &mut Attack,
-        &mut Defense,
-        &mut Damage,
-        &mut SpecialAbilities,
-    ), With<Player>>,
+    mut stats: ResMut<RunStats>,
+    mut player_query: PlayerUpgradeQuery,
 ) {
-    if let Ok((mut health, mut armor, mut attack, mut defense, mut damage, mut abilities)) = player_query.single_mut() {
-        let room_index = if world.current_room_index > 0 {
-            world.current_room_index - 1
-        } else {
-            return;
-        };
+    for event in combat_end_events.read() {
+        if !event.player_won || world.is_cleared() {
+            continue;
+        }
         
-        let room = world.rooms.get(room_index);
-        if let Some(&room) = room {
-            if let Some(upgrade) = UpgradeTypeData::for_room(room) {
-                let stats = upgrade.stats();
-                health.current += stats.0;
-                health.max += stats.1;
-                armor.0 += stats.2;
-                attack.0 += stats.4;
-                defense.0 += stats.5;
-                damage.0 += stats.6;
-                abilities.finte_level += stats.7;
-                abilities.wuchtschlag_level += stats.8;
-                
-                info!("Applied upgrade: {:?}", upgrade);
-            }
+        // Runs before handle_combat_end_system, so current room is the one just won
+        if let Some(upgrade) = world.current().upgrade {
+            apply_upgrade(upgrade, world.difficulty, &mut player_query);
+            stats.upgrades_taken += 1;
         }
     }
-}
\ No newline at end of file
+}
+
+fn apply_upgrade(upgrade: UpgradeTypeData, difficulty: Difficulty, player_query: &mut PlayerUpgradeQuery) {
+    let Ok((mut health, mut armor, mut attack, mut defense, mut damage, mut abilities)) = player_query.single_mut() else {
+        return;
+    };
+    
+    let stats = difficulty.upgrade_stats(upgrade);
+    health.current += stats.0;
+    health.max += stats.1;
+    armor.0 += stats.2;
+    attack.0 += stats.4;
+    defense.0 += stats.5;
+    damage.0 += stats.6;
+    abilities.finte_level += stats.7;
+    abilities.wuchtschlag_level += stats.8;
+    
+    info!("Applied upgrade: {:?}", upgrade);
+}
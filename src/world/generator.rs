@@ -0,0 +1,119 @@
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+use super::data::{FleeRule, RoomExit, RoomTypeData, UpgradeTypeData};
+use super::encounters::{pick_encounter, EncounterBudget, EncounterTable};
+use super::resources::{Room, RoomGraph, WorldState};
+
+// Fight rooms a generated dungeon is built from, each brings its enemy tags and reward
+const ROOM_TEMPLATES: [RoomTypeData; 6] = [
+    RoomTypeData::FloorRoom,
+    RoomTypeData::Pantry1,
+    RoomTypeData::LibraryRoom,
+    RoomTypeData::DiningHall,
+    RoomTypeData::Laboratory,
+    RoomTypeData::Corridor,
+];
+
+// Layers from the start room to the boss, both included
+const MIN_LAYERS: usize = 6;
+const MAX_LAYERS: usize = 8;
+const MAX_ROOMS_PER_LAYER: usize = 2;
+
+// Threat an encounter may add up to, grows with every layer
+const BASE_BUDGET: u32 = 8;
+const BUDGET_PER_DEPTH: u32 = 8;
+
+// Builds a dungeon from a seed, the same seed always gives the same dungeon.
+// Start room, fight rooms in one or two branches, a treasure room somewhere in the
+// middle, a rest room right before the boss and the boss room at the end.
+pub fn generate_dungeon(seed: u64, encounters: &EncounterTable) -> WorldState {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let layer_count = rng.random_range(MIN_LAYERS..=MAX_LAYERS);
+    let rest_layer = layer_count - 2;
+    let treasure_layer = rng.random_range(1..rest_layer);
+
+    let mut rooms = vec![generated_room(RoomTypeData::IntroRoom, 0, encounters, &mut rng)];
+    let mut previous_layer = vec![0];
+    for depth in 1..layer_count {
+        let kinds = if depth == layer_count - 1 {
+            vec![RoomTypeData::FinalRoom]
+        } else if depth == rest_layer {
+            vec![RoomTypeData::RestRoom]
+        } else {
+            let width = rng.random_range(1..=MAX_ROOMS_PER_LAYER);
+            let mut kinds: Vec<RoomTypeData> = (0..width)
+                .map(|_| *ROOM_TEMPLATES.choose(&mut rng).expect("templates not empty"))
+                .collect();
+            if depth == treasure_layer {
+                let slot = rng.random_range(0..width);
+                kinds[slot] = RoomTypeData::TreasureRoom;
+            }
+            kinds
+        };
+
+        let layer: Vec<usize> = kinds.into_iter()
+            .map(|kind| {
+                rooms.push(generated_room(kind, depth, encounters, &mut rng));
+                rooms.len() - 1
+            })
+            .collect();
+        connect_layers(&mut rooms, &previous_layer, &layer, &mut rng);
+        previous_layer = layer;
+    }
+
+    WorldState {
+        seed: Some(seed),
+        ..WorldState::from_graph(RoomGraph { rooms, start: 0 })
+    }
+}
+
+// Fresh random seed for the menu
+pub fn random_seed() -> u64 {
+    rand::rng().random_range(0..1_000_000)
+}
+
+fn generated_room(kind: RoomTypeData, depth: usize, encounters: &EncounterTable, rng: &mut StdRng) -> Room {
+    let mut room = Room::new(kind);
+    // Fights can be fled back to the room before, except the first and the boss fight
+    room.flee = match kind {
+        RoomTypeData::IntroRoom | RoomTypeData::FinalRoom => FleeRule::Forbidden,
+        _ => FleeRule::ToPrevious,
+    };
+    match kind {
+        // The boss fight stays as designed
+        RoomTypeData::FinalRoom => {}
+        RoomTypeData::TreasureRoom => {
+            room.upgrade = UpgradeTypeData::ALL.choose(rng).copied();
+        }
+        _ => {
+            let budget = BASE_BUDGET + depth as u32 * BUDGET_PER_DEPTH;
+            room.enemies = pick_encounter(&EncounterBudget::new(budget, kind.encounter_tags()), encounters, rng);
+        }
+    }
+    room
+}
+
+// Every room gets one or two doors forward, every room of the next layer at least one door in
+fn connect_layers(rooms: &mut [Room], from: &[usize], to: &[usize], rng: &mut StdRng) {
+    for &room in from {
+        let door_count = rng.random_range(1..=to.len());
+        let targets: Vec<usize> = to.choose_multiple(rng, door_count).copied().collect();
+        for target in targets {
+            add_exit(rooms, room, target);
+        }
+    }
+    for &target in to {
+        let reachable = from.iter().any(|&room| rooms[room].exits.iter().any(|exit| exit.to == target));
+        if !reachable {
+            let room = *from.choose(rng).expect("layer not empty");
+            add_exit(rooms, room, target);
+        }
+    }
+}
+
+fn add_exit(rooms: &mut [Room], from: usize, to: usize) {
+    let hint = rooms[to].kind.door_hint_key().to_string();
+    rooms[from].exits.push(RoomExit { to, hint });
+    rooms[from].exits.sort_by_key(|exit| exit.to);
+}
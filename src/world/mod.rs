@@ -1,39 +1,97 @@
 use bevy::prelude::*;
 use crate::game_state::GameState;
+use crate::combat::{check_victory_system, enemy_ai_system, execute_attack_system, execute_flee_system, process_turn_system};
 
 pub mod data;
 pub mod resources;
 pub mod gameplay_systems;
+pub mod generator;
+pub mod encounters;
+pub mod bosses;
+pub mod character_configs;
+pub mod sprites;
+pub mod difficulty;
+pub mod save;
+pub mod run_stats;
+pub mod rest;
 
 pub use data::*;
 pub use resources::*;
 pub use gameplay_systems::*;
+pub use generator::*;
+pub use encounters::*;
+pub use bosses::*;
+pub use character_configs::*;
+pub use sprites::*;
+pub use difficulty::*;
+pub use save::*;
+pub use run_stats::*;
+pub use rest::*;
 
 pub fn plugin(app: &mut App) {
     app
         .init_resource::<WorldState>()
+        .init_resource::<RoomGraph>()
+        .init_resource::<RunSetup>()
+        .init_resource::<EncounterTable>()
+        .init_resource::<BossScripts>()
+        .init_resource::<SpriteSheets>()
+        .init_resource::<RunStats>()
+        .init_resource::<RestRules>()
+        .add_systems(Startup, (
+            load_room_graph_system,
+            load_enemy_configs_system,
+            load_class_configs_system,
+            load_rest_rules_system,
+            load_save_game_system,
+        ))
+        
+        // New run: fixed or generated dungeon, the player lives until the run ends
+        .add_systems(OnTransition {
+            exited: GameState::CharacterSelection,
+            entered: GameState::Gameplay,
+        }, (start_run_system, spawn_player_system).chain())
+        .add_systems(OnEnter(GameState::MainMenu), cleanup_run_system)
         
         // Gameplay systems
-        .add_systems(OnEnter(GameState::Gameplay), (
-            spawn_player_system,
-            load_room_system,
-        ))
+        .add_systems(OnEnter(GameState::Gameplay), load_room_system)
         .add_systems(Update, (
+            camp_system,
+            continue_after_flee_system,
+            choose_door_system,
+            clear_peaceful_room_system,
             start_combat_when_ready_system,
-        ).run_if(in_state(GameState::Gameplay)))
+        ).chain().run_if(in_state(GameState::Gameplay)))
         
-        // Combat aftermath systems
+        // Mid-combat systems, before the turn queue gets rebuilt
         .add_systems(Update, (
-            handle_combat_end_system,
+            spawn_reinforcements_system,
+            boss_script_system,
+        ).before(process_turn_system).run_if(in_state(GameState::Combat)))
+        
+        // Bosses adjust their queued attack before it is resolved
+        .add_systems(Update, boss_turn_system
+            .after(enemy_ai_system)
+            .before(execute_attack_system)
+            .run_if(in_state(GameState::Combat)))
+        
+        // Combat aftermath systems, in the same frame the fight ends
+        .add_systems(Update, (
+            track_combat_end_stats_system,
             apply_upgrades_system,
-        ).run_if(in_state(GameState::Combat)))
+            handle_combat_end_system,
+        ).chain().after(check_victory_system).after(execute_flee_system).run_if(in_state(GameState::Combat)))
         
-        // Cleanup when leaving gameplay
-        .add_systems(OnExit(GameState::Gameplay), cleanup_player_system);
+        // Run summary numbers
+        .add_systems(Update, track_combat_stats_system
+            .after(execute_attack_system)
+            .run_if(in_state(GameState::Combat)))
+        .add_systems(Update, track_time_played_system
+            .run_if(in_state(GameState::Gameplay).or(in_state(GameState::Combat))));
 }
 
-// Cleanup player when returning to menu
-fn cleanup_player_system(
+// Cleanup player and enemies when the run ends
+pub fn cleanup_run_system(
     mut commands: Commands,
     player_query: Query<Entity, With<crate::character::Player>>,
     enemy_query: Query<Entity, With<crate::character::Enemy>>,
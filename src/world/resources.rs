@@ -1,52 +1,309 @@
+use std::collections::{HashMap, HashSet};
 use bevy::prelude::*;
-use super::data::RoomTypeData;
+use serde::Deserialize;
+use rand::Rng;
+use crate::config::Config;
+use crate::localization::Localization;
+use super::data::{EnemyTypeData, FleeRule, Reinforcements, RoomExit, RoomTypeData, UpgradeTypeData};
+use super::encounters::{pick_encounter, EncounterBudget, EncounterTable};
+use super::difficulty::Difficulty;
+
+// One room of a dungeon; a room type can show up more than once in generated dungeons
+#[derive(Clone, Debug)]
+pub struct Room {
+    pub kind: RoomTypeData,
+    pub enemies: Vec<EnemyTypeData>,
+    pub budget: Option<EncounterBudget>, // enemies are rolled at the start of every run
+    pub upgrade: Option<UpgradeTypeData>,
+    pub reinforcements: Option<Reinforcements>,
+    pub flee: FleeRule,
+    pub exits: Vec<RoomExit>,
+}
+
+impl Room {
+    // Enemies and reward of the fixed room type, no escape until a flee rule is set
+    pub fn new(kind: RoomTypeData) -> Self {
+        Self {
+            kind,
+            enemies: kind.enemies(),
+            budget: None,
+            upgrade: UpgradeTypeData::for_room(kind),
+            reinforcements: None,
+            flee: FleeRule::default(),
+            exits: Vec::new(),
+        }
+    }
+}
+
+// Rooms and the doors between them, loaded from assets/config/rooms.ron
+#[derive(Resource, Clone, Debug)]
+pub struct RoomGraph {
+    pub rooms: Vec<Room>,
+    pub start: usize,
+}
+
+// One entry in rooms.ron, only the fields the graph needs
+#[derive(Deserialize)]
+struct RoomConfig {
+    id: RoomTypeData,
+    #[serde(default)]
+    exits: Vec<RoomExitConfig>,
+    // e.g. budget: 30, tags: ["zombie"] instead of the fixed enemies of the room type
+    #[serde(default)]
+    budget: u32,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    reinforcements: Option<Reinforcements>,
+    // e.g. flee: ToPrevious, rooms without it can't be fled from
+    #[serde(default)]
+    flee: FleeRule,
+}
+
+#[derive(Deserialize)]
+struct RoomExitConfig {
+    to: RoomTypeData,
+    hint: String,
+}
+
+impl Config for RoomGraph {
+    // The first room in the file is the start room
+    fn from_ron(content: &str) -> Result<Self, String> {
+        let configs = ron::from_str::<Vec<RoomConfig>>(content).map_err(|err| err.to_string())?;
+        if configs.is_empty() {
+            return Err("no rooms defined".to_string());
+        }
+        let index_of: HashMap<RoomTypeData, usize> = configs.iter()
+            .enumerate()
+            .map(|(index, config)| (config.id, index))
+            .collect();
+
+        let mut rooms = Vec::new();
+        for config in configs {
+            let mut room = Room::new(config.id);
+            if config.budget > 0 {
+                room.budget = Some(EncounterBudget { budget: config.budget, tags: config.tags });
+            }
+            room.reinforcements = config.reinforcements;
+            room.flee = config.flee;
+            for exit in config.exits {
+                let to = *index_of.get(&exit.to).ok_or(format!("exit to unknown room {:?}", exit.to))?;
+                room.exits.push(RoomExit { to, hint: exit.hint });
+            }
+            rooms.push(room);
+        }
+        Ok(Self { rooms, start: 0 })
+    }
+}
+
+impl RoomGraph {
+    pub fn exits(&self, room: usize) -> &[RoomExit] {
+        &self.rooms[room].exits
+    }
+
+    // Roll the enemies of rooms that only give a budget
+    pub fn roll_encounters(&mut self, table: &EncounterTable, rng: &mut impl Rng) {
+        for room in &mut self.rooms {
+            if let Some(budget) = &room.budget {
+                room.enemies = pick_encounter(budget, table, rng);
+            }
+        }
+    }
+
+    // Rooms grouped by their longest distance from the start room, in door order.
+    // A linear layout gives one room per layer, branches share a layer.
+    pub fn layers(&self) -> Vec<Vec<usize>> {
+        // Discovery order from the start room, so the first door stays on top
+        let mut order = vec![self.start];
+        let mut index = 0;
+        while let Some(&room) = order.get(index) {
+            for exit in self.exits(room) {
+                if !order.contains(&exit.to) {
+                    order.push(exit.to);
+                }
+            }
+            index += 1;
+        }
+
+        // Longest path, capped at the room count so loops can't run forever
+        let mut layer_of: HashMap<usize, usize> = HashMap::from([(self.start, 0)]);
+        for _ in 0..order.len() {
+            for &room in &order {
+                let Some(&layer) = layer_of.get(&room) else { continue };
+                for exit in self.exits(room) {
+                    let next_layer = layer + 1;
+                    if next_layer < order.len() && layer_of.get(&exit.to).is_none_or(|&l| l < next_layer) {
+                        layer_of.insert(exit.to, next_layer);
+                    }
+                }
+            }
+        }
+
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+        for room in order {
+            let layer = layer_of[&room];
+            if layers.len() <= layer {
+                layers.resize(layer + 1, Vec::new());
+            }
+            layers[layer].push(room);
+        }
+        layers
+    }
+}
+
+impl Default for RoomGraph {
+    // Same layout as rooms.ron: two ways through the mansion that meet in the corridor
+    fn default() -> Self {
+        use RoomTypeData::*;
+        use FleeRule::*;
+        let layout = [
+            (IntroRoom, Forbidden, vec![(FloorRoom, "door.intro.left"), (Pantry1, "door.intro.right")]),
+            (FloorRoom, ToPrevious, vec![(LibraryRoom, "door.floor.library"), (DiningHall, "door.floor.dining_hall")]),
+            (Pantry1, ToNext, vec![(DiningHall, "door.pantry.kitchen")]),
+            (LibraryRoom, ToPrevious, vec![(Laboratory, "door.library.laboratory")]),
+            (DiningHall, ToNext, vec![(Laboratory, "door.dining_hall.laboratory"), (Corridor, "door.dining_hall.corridor")]),
+            (Laboratory, ToPrevious, vec![(Corridor, "door.laboratory.corridor")]),
+            (Corridor, ToPrevious, vec![(FinalRoom, "door.corridor.final")]),
+            (FinalRoom, Forbidden, vec![]),
+        ];
+        let index_of = |kind| layout.iter().position(|(room, ..)| *room == kind).expect("room in layout");
+        let rooms = layout.iter()
+            .map(|(kind, flee, exits)| Room {
+                flee: *flee,
+                exits: exits.iter()
+                    .map(|&(to, hint)| RoomExit { to: index_of(to), hint: hint.to_string() })
+                    .collect(),
+                ..Room::new(*kind)
+            })
+            .collect();
+        Self { rooms, start: 0 }
+    }
+}
+
+// How the next run is set up, filled in by the main menu
+#[derive(Resource, Default)]
+pub struct RunSetup {
+    pub seed: Option<u64>, // Some: generated dungeon ("Zufallsdungeon")
+    pub difficulty: Difficulty, // picked on the character screen, preselected from the save file
+    pub permadeath: bool, // same, a lost fight ends the run without a retry
+}
 
 #[derive(Resource)]
 pub struct WorldState {
-    pub rooms: Vec<RoomTypeData>,
-    pub current_room_index: usize,
-    pub total_rooms: usize,
+    pub graph: RoomGraph,
+    pub path: Vec<usize>, // rooms walked through, the last one is the current room
+    pub door_choice: Option<usize>, // highlighted door while the player picks the next room
+    pub visited: HashSet<usize>, // stays filled after retreating, for the map
+    pub cleared: HashSet<usize>, // won or looted, no enemies or rewards when coming back
+    pub fled: bool, // the player fled into the current room, the flee text shows until Confirm
+    pub room_start_health: Option<u32>, // player HP when the fight in the current room started, a retry restores it
+    pub seed: Option<u64>, // set for generated dungeons
+    pub difficulty: Difficulty,
+    pub permadeath: bool,
+    pub camps_left: u32, // set from the rest rules when the run starts
 }
 
 impl WorldState {
     pub fn new() -> Self {
-        let rooms = vec![
-            RoomTypeData::IntroRoom,
-            RoomTypeData::FloorRoom,
-            RoomTypeData::Pantry1,
-            RoomTypeData::LibraryRoom,
-            RoomTypeData::DiningHall,
-            RoomTypeData::Laboratory,
-            RoomTypeData::Corridor,
-            RoomTypeData::FinalRoom,
-        ];
-        let total = rooms.len();
+        Self::from_graph(RoomGraph::default())
+    }
+
+    pub fn from_graph(graph: RoomGraph) -> Self {
         Self {
-            rooms,
-            current_room_index: 0,
-            total_rooms: total,
+            path: vec![graph.start],
+            visited: HashSet::from([graph.start]),
+            cleared: HashSet::new(),
+            fled: false,
+            room_start_health: None,
+            graph,
+            door_choice: None,
+            seed: None,
+            difficulty: Difficulty::default(),
+            permadeath: false,
+            camps_left: 0,
         }
     }
-    
+
+    pub fn current_index(&self) -> usize {
+        *self.path.last().expect("path always contains the start room")
+    }
+
+    pub fn current(&self) -> &Room {
+        &self.graph.rooms[self.current_index()]
+    }
+
     pub fn current_room(&self) -> RoomTypeData {
-        self.rooms[self.current_room_index]
+        self.current().kind
+    }
+
+    pub fn exits(&self) -> &[RoomExit] {
+        &self.current().exits
+    }
+
+    // Go through the door with the given index
+    pub fn enter(&mut self, exit_index: usize) -> bool {
+        let Some(next) = self.exits().get(exit_index).map(|exit| exit.to) else {
+            return false;
+        };
+        self.path.push(next);
+        self.visited.insert(next);
+        self.door_choice = None;
+        true
+    }
+
+    pub fn is_cleared(&self) -> bool {
+        self.cleared.contains(&self.current_index())
+    }
+
+    pub fn mark_cleared(&mut self) {
+        let room = self.current_index();
+        self.cleared.insert(room);
     }
-    
-    pub fn advance(&mut self) -> bool {
-        if self.has_next_room() {
-            self.current_room_index += 1;
+
+    pub fn retreat(&mut self) -> bool {
+        if self.path.len() > 1 {
+            self.path.pop();
             true
         } else {
             false
         }
     }
-    
+
+    // Rooms the player has seen a door to but not entered yet
+    pub fn is_known(&self, room: usize) -> bool {
+        !self.visited.contains(&room)
+            && self.visited.iter().any(|&visited| self.graph.exits(visited).iter().any(|exit| exit.to == room))
+    }
+
     pub fn has_next_room(&self) -> bool {
-        self.current_room_index < self.total_rooms - 1
+        !self.exits().is_empty()
     }
-    
-    pub fn progress(&self) -> String {
-        format!("Raum {} von {}", self.current_room_index + 1, self.total_rooms)
+
+    // The flee rule allows it and there is a room to flee into
+    pub fn can_flee(&self) -> bool {
+        match self.current().flee {
+            FleeRule::Forbidden => false,
+            FleeRule::ToPrevious => self.path.len() > 1 || self.has_next_room(),
+            FleeRule::ToNext => self.has_next_room(),
+        }
+    }
+
+    // How many rooms deep the player is, the start room is depth 1
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    pub fn progress(&self, loc: &Localization) -> String {
+        loc.t_with("hud.depth", &[("depth", &self.depth())])
+    }
+
+    // e.g. "Schwierigkeit: Schwer (Permadeath)"
+    pub fn difficulty_text(&self, loc: &Localization) -> String {
+        let mut text = loc.t_with("hud.difficulty", &[("difficulty", &loc.t(self.difficulty.name_key()))]);
+        if self.permadeath {
+            text.push_str(&loc.t("hud.permadeath"));
+        }
+        text
     }
 }
 
@@ -54,4 +311,4 @@ impl Default for WorldState {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use crate::character::Health;
+use crate::config::Config;
+
+// Healing between fights, loaded from assets/config/rest.ron
+#[derive(Resource, Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RestRules {
+    pub rest_room_heal_percent: u32, // of max HP, healed in rest rooms
+    pub camp_heal_percent: u32, // of max HP, healed by camping after a fight
+    pub camp_uses: u32, // camps per run
+    pub ambush_chance_percent: u32, // chance that enemies interrupt the camp
+    pub ambush_damage_percent: u32, // of max HP, lost when ambushed
+}
+
+impl Default for RestRules {
+    fn default() -> Self {
+        Self {
+            rest_room_heal_percent: 50,
+            camp_heal_percent: 25,
+            camp_uses: 2,
+            ambush_chance_percent: 20,
+            ambush_damage_percent: 10,
+        }
+    }
+}
+
+// What happened when the player tried to camp
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CampOutcome {
+    Rested { healed: u32 },
+    Ambushed { damage: u32 },
+    NoCampsLeft,
+}
+
+impl Config for RestRules {
+    fn from_ron(content: &str) -> Result<Self, String> {
+        ron::from_str(content).map_err(|err| err.to_string())
+    }
+}
+
+impl RestRules {
+    // Share of max HP, rounded up so small percentages still do something
+    pub fn percent_of(percent: u32, max: u32) -> u32 {
+        (max * percent).div_ceil(100)
+    }
+
+    // Heal in a rest room, returns the HP actually gained
+    pub fn rest(&self, health: &mut Health) -> u32 {
+        let before = health.current;
+        health.heal(Self::percent_of(self.rest_room_heal_percent, health.max));
+        health.current - before
+    }
+
+    // Camp between fights: uses up one camp, an ambush costs HP instead of healing (never the last one)
+    pub fn camp(&self, camps_left: &mut u32, health: &mut Health, rng: &mut impl Rng) -> CampOutcome {
+        if *camps_left == 0 {
+            return CampOutcome::NoCampsLeft;
+        }
+        *camps_left -= 1;
+
+        if rng.random_range(0..100) < self.ambush_chance_percent {
+            let damage = Self::percent_of(self.ambush_damage_percent, health.max)
+                .min(health.current.saturating_sub(1));
+            health.current -= damage;
+            return CampOutcome::Ambushed { damage };
+        }
+
+        let before = health.current;
+        health.heal(Self::percent_of(self.camp_heal_percent, health.max));
+        CampOutcome::Rested { healed: health.current - before }
+    }
+}
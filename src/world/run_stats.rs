@@ -0,0 +1,85 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use crate::character::{Enemy, Health, Player};
+use crate::combat::{AttackOutcome, AttackResolvedEvent, CombatEndEvent, CombatStartEvent, RoundStartEvent};
+use crate::localization::Localization;
+use super::resources::WorldState;
+
+// Numbers for the game over and victory screens, reset when a run starts
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct RunStats {
+    pub rooms_cleared: u32,
+    pub enemies_killed: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub rounds_fought: u32,
+    pub upgrades_taken: u32,
+    pub time_played: Duration,
+}
+
+impl RunStats {
+    pub fn record_hit(&mut self, damage: u32, by_player: bool, on_player: bool) {
+        if by_player {
+            self.damage_dealt += damage;
+        }
+        if on_player {
+            self.damage_taken += damage;
+        }
+    }
+
+    // Minutes and seconds, e.g. "12:05"
+    pub fn time_text(&self) -> String {
+        let seconds = self.time_played.as_secs();
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+
+    pub fn summary(&self, loc: &Localization) -> String {
+        loc.t_with("summary.stats", &[
+            ("rooms", &self.rooms_cleared),
+            ("kills", &self.enemies_killed),
+            ("dealt", &self.damage_dealt),
+            ("taken", &self.damage_taken),
+            ("rounds", &self.rounds_fought),
+            ("upgrades", &self.upgrades_taken),
+            ("time", &self.time_text()),
+        ])
+    }
+}
+
+// System that counts rounds and damage of the player's fights
+pub fn track_combat_stats_system(
+    mut stats: ResMut<RunStats>,
+    mut attack_events: MessageReader<AttackResolvedEvent>,
+    mut combat_start_events: MessageReader<CombatStartEvent>,
+    mut round_events: MessageReader<RoundStartEvent>,
+    player_query: Query<(), With<Player>>,
+) {
+    for event in attack_events.read() {
+        if let AttackOutcome::Hit { damage } = event.outcome {
+            stats.record_hit(damage, player_query.contains(event.attacker), player_query.contains(event.target));
+        }
+    }
+    // Round 1 starts with the fight, only the later ones send a RoundStartEvent
+    stats.rounds_fought += combat_start_events.read().count() as u32;
+    stats.rounds_fought += round_events.read().count() as u32;
+}
+
+// System that counts the dead enemies and won rooms before they get despawned
+pub fn track_combat_end_stats_system(
+    mut stats: ResMut<RunStats>,
+    mut combat_end_events: MessageReader<CombatEndEvent>,
+    world: Res<WorldState>,
+    enemy_query: Query<&Health, With<Enemy>>,
+) {
+    for event in combat_end_events.read() {
+        stats.enemies_killed += enemy_query.iter().filter(|health| !health.is_alive()).count() as u32;
+        // A room won before only counts once
+        if event.player_won && !world.is_cleared() {
+            stats.rooms_cleared += 1;
+        }
+    }
+}
+
+pub fn track_time_played_system(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.time_played += time.delta();
+}
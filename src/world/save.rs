@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::difficulty::Difficulty;
+use super::resources::{RunSetup, WorldState};
+
+pub const SAVE_PATH: &str = "saves/savegame.ron";
+
+// Setup of the last run, saved to saves/savegame.ron when a run starts.
+// The next run starts with it preselected, runs themselves are not saved.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveGame {
+    pub difficulty: Difficulty,
+    pub permadeath: bool,
+}
+
+impl SaveGame {
+    pub fn from_world(world: &WorldState) -> Self {
+        Self {
+            difficulty: world.difficulty,
+            permadeath: world.permadeath,
+        }
+    }
+
+    pub fn from_ron(content: &str) -> Result<Self, String> {
+        ron::from_str(content).map_err(|err| err.to_string())
+    }
+
+    pub fn load(path: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        match Self::from_ron(&content) {
+            Ok(save) => Some(save),
+            Err(err) => {
+                warn!("Invalid save file {}: {}", path, err);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)?;
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)
+    }
+}
+
+// System that preselects the difficulty and permadeath of the last run
+pub fn load_save_game_system(mut run_setup: ResMut<RunSetup>) {
+    if let Some(save) = SaveGame::load(SAVE_PATH) {
+        run_setup.difficulty = save.difficulty;
+        run_setup.permadeath = save.permadeath;
+        info!("Loaded save file, last difficulty {:?}, permadeath {}", save.difficulty, save.permadeath);
+    }
+}
+
+// Remember the setup of the run that just started
+pub fn write_save_game(world: &WorldState) {
+    if let Err(err) = SaveGame::from_world(world).save(SAVE_PATH) {
+        warn!("Could not write save file {}: {}", SAVE_PATH, err);
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+use crate::character::SpriteSheet;
+use super::data::{EnemyTypeData, PlayerTypeData};
+use super::character_configs::{ClassConfigs, EnemyConfigs};
+
+// Drawn for every enemy or class without a sprite field
+const FALLBACK_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
+// Sprite sheet per enemy type and player class, from the sprite fields in enemies.ron/classes.ron
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SpriteSheets {
+    enemies: HashMap<EnemyTypeData, SpriteSheet>,
+    players: HashMap<PlayerTypeData, SpriteSheet>,
+}
+
+// All character sheets share one layout: 64x64 frames, 4x4 grid,
+// one row per animation (idle, attack, hit, death)
+fn character_sheet(path: String, fallback_color: Color) -> SpriteSheet {
+    SpriteSheet {
+        path,
+        tile_size: UVec2::new(64, 64),
+        columns: 4,
+        rows: 4,
+        idle: (0, 3),
+        attack: (4, 7),
+        hit: (8, 9),
+        death: (12, 15),
+        fallback_color,
+    }
+}
+
+// No texture, only the colored rectangle
+fn fallback_sheet() -> SpriteSheet {
+    character_sheet(String::new(), FALLBACK_COLOR)
+}
+
+// sprite field of an entry, e.g. sprite: Some((path: "textures/...", fallback_color: (0.4, 0.7, 0.3)))
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpriteConfig {
+    pub path: String,
+    pub fallback_color: (f32, f32, f32),
+}
+
+impl SpriteConfig {
+    fn sheet(&self) -> SpriteSheet {
+        let (r, g, b) = self.fallback_color;
+        character_sheet(self.path.clone(), Color::srgb(r, g, b))
+    }
+}
+
+impl SpriteSheets {
+    // Sprites from enemies.ron, enemies without one are drawn as the fallback rectangle
+    pub fn load_enemies(&mut self, enemies: &EnemyConfigs) {
+        for (&enemy, config) in &enemies.0 {
+            if let Some(sprite) = &config.sprite {
+                self.enemies.insert(enemy, sprite.sheet());
+            }
+        }
+    }
+
+    // Sprites from classes.ron, classes without one are drawn as the fallback rectangle
+    pub fn load_classes(&mut self, classes: &ClassConfigs) {
+        for (&player, config) in &classes.0 {
+            if let Some(sprite) = &config.sprite {
+                self.players.insert(player, sprite.sheet());
+            }
+        }
+    }
+
+    pub fn enemy(&self, enemy: EnemyTypeData) -> SpriteSheet {
+        self.enemies.get(&enemy).cloned().unwrap_or_else(fallback_sheet)
+    }
+
+    pub fn player(&self, player: PlayerTypeData) -> SpriteSheet {
+        self.players.get(&player).cloned().unwrap_or_else(fallback_sheet)
+    }
+}
@@ -0,0 +1,17 @@
+use bevy::audio::Volume;
+use informatik_game_bevy::settings::{AudioChannel, GameSettings};
+
+#[test]
+fn channels_use_their_own_volume() {
+    let settings = GameSettings { music_volume: 0.3, sfx_volume: 0.6, ..Default::default() };
+    assert_eq!(AudioChannel::Music.volume(&settings), Volume::Linear(0.3));
+    assert_eq!(AudioChannel::Sfx.volume(&settings), Volume::Linear(0.6));
+}
+
+#[test]
+fn channel_volumes_are_saved() {
+    let settings = GameSettings { music_volume: 0.2, sfx_volume: 0.9, ..Default::default() };
+    let content = ron::to_string(&settings).expect("settings serialize");
+    let loaded: GameSettings = ron::from_str(&content).expect("settings deserialize");
+    assert_eq!((loaded.music_volume, loaded.sfx_volume), (0.2, 0.9));
+}
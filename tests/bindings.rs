@@ -0,0 +1,23 @@
+use std::collections::HashMap;
+use informatik_game_bevy::input::{GameAction, InputBindings, InputDevice};
+
+#[test]
+fn default_bindings_are_unique() {
+    let bindings = InputBindings::default();
+    let mut owners = HashMap::new();
+    for (action, inputs) in &bindings.bindings {
+        for input in inputs {
+            if let Some(other) = owners.insert(*input, *action) {
+                panic!("{:?} is bound to both {:?} and {:?}", input, other, action);
+            }
+        }
+    }
+}
+
+#[test]
+fn every_rebindable_action_has_a_pad_button() {
+    let bindings = InputBindings::default();
+    for action in GameAction::REBINDABLE {
+        assert!(bindings.get(action).iter().any(|input| input.device() == InputDevice::Gamepad), "{:?}", action);
+    }
+}
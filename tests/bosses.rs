@@ -0,0 +1,60 @@
+use informatik_game_bevy::localization::{Localization, LOCALE_DIR};
+use informatik_game_bevy::config::Config;
+use informatik_game_bevy::world::{BossScripts, EnemyConfigs, EnemyTypeData};
+
+fn parse_scripts(content: &str) -> Result<BossScripts, String> {
+    EnemyConfigs::from_ron(content).map(|enemies| BossScripts::from_enemies(&enemies))
+}
+
+fn config_scripts() -> BossScripts {
+    let content = std::fs::read_to_string("assets/config/enemies.ron").expect("enemies.ron exists");
+    parse_scripts(&content).expect("valid boss scripts")
+}
+
+#[test]
+fn endboss_script_comes_from_the_config() {
+    let scripts = config_scripts();
+    assert!(!scripts.is_boss(EnemyTypeData::MiniZombie));
+    let script = scripts.get(EnemyTypeData::Endboss).expect("endboss has a script");
+    assert!(!script.phases.is_empty());
+    assert!(script.phases.windows(2).all(|pair| pair[0].hp_percent > pair[1].hp_percent), "phases in falling order");
+    assert!(script.special_attack.is_some());
+    assert_eq!(script.summons[0].enemy, EnemyTypeData::MiniZombie);
+}
+
+#[test]
+fn boss_texts_are_localization_keys() {
+    let loc = Localization::load(LOCALE_DIR);
+    let script = config_scripts().get(EnemyTypeData::Endboss).cloned().expect("endboss has a script");
+    let special = script.special_attack.expect("special attack");
+    let keys = script.phases.iter().map(|phase| &phase.message)
+        .chain(script.summons.iter().map(|summon| &summon.message))
+        .chain([&special.name, &special.announcement]);
+    for key in keys {
+        assert_ne!(&loc.t(key), key, "missing key {}", key);
+    }
+}
+
+#[test]
+fn enemies_without_boss_field_fight_normally() {
+    let scripts = parse_scripts(r#"{ "Endboss": (tags: ["boss"]) }"#).expect("valid boss scripts");
+    assert!(!scripts.is_boss(EnemyTypeData::Endboss));
+    assert!(parse_scripts(r#"{ "Dragon": (boss: Some(())) }"#).is_err());
+}
+
+#[test]
+fn phases_and_summons_are_sorted_by_threshold() {
+    let scripts = parse_scripts(r#"{ "Endboss": (boss: Some((
+        phases: [
+            (hp_percent: 20, ai: AllOut, message: "late"),
+            (hp_percent: 70, ai: Aggressive, message: "early"),
+        ],
+        summons: [
+            (hp_percent: 10, enemy: Scientist, count: 1, message: "late"),
+            (hp_percent: 50, enemy: MiniZombie, count: 2, message: "early"),
+        ],
+    ))) }"#).expect("valid boss scripts");
+    let script = scripts.get(EnemyTypeData::Endboss).expect("endboss has a script");
+    assert_eq!(script.phases.iter().map(|phase| phase.hp_percent).collect::<Vec<_>>(), vec![70, 20]);
+    assert_eq!(script.summons.iter().map(|summon| summon.hp_percent).collect::<Vec<_>>(), vec![50, 10]);
+}
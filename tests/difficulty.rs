@@ -0,0 +1,67 @@
+use informatik_game_bevy::localization::{Localization, LOCALE_DIR};
+use informatik_game_bevy::world::{new_run, Difficulty, EncounterTable, EnemyTypeData, RoomGraph, RunSetup, SaveGame, UpgradeTypeData};
+
+#[test]
+fn normal_keeps_enemies_and_rewards_as_they_are() {
+    let loc = Localization::load(LOCALE_DIR);
+    for enemy_type in EnemyTypeData::ALL {
+        let mut enemy = enemy_type.bundle(&loc);
+        let (health, attack, damage, dice) = (enemy.health.max, enemy.attack.0, enemy.damage.0, enemy.dice.0);
+        Difficulty::Normal.apply_to_enemy(&mut enemy);
+        assert_eq!((enemy.health.max, enemy.attack.0, enemy.damage.0, enemy.dice.0), (health, attack, damage, dice));
+    }
+    for upgrade in UpgradeTypeData::ALL {
+        assert_eq!(Difficulty::Normal.upgrade_stats(upgrade), upgrade.stats(), "{:?}", upgrade);
+    }
+}
+
+#[test]
+fn harder_levels_make_enemies_stronger() {
+    let loc = Localization::load(LOCALE_DIR);
+    for enemy_type in EnemyTypeData::ALL {
+        let stats: Vec<(u32, u32, u32, u32)> = Difficulty::ALL.iter()
+            .map(|difficulty| {
+                let mut enemy = enemy_type.bundle(&loc);
+                difficulty.apply_to_enemy(&mut enemy);
+                assert_eq!(enemy.health.current, enemy.health.max);
+                (enemy.health.max, enemy.attack.0, enemy.damage.0, enemy.dice.0)
+            })
+            .collect();
+        assert!(stats.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}: {:?}", enemy_type, stats);
+        assert!(stats[0].0 < stats[3].0, "{:?}: {:?}", enemy_type, stats);
+    }
+}
+
+#[test]
+fn rewards_shrink_but_never_vanish() {
+    for upgrade in UpgradeTypeData::ALL {
+        let normal = upgrade.stats();
+        let easy = Difficulty::Easy.upgrade_stats(upgrade);
+        let nightmare = Difficulty::Nightmare.upgrade_stats(upgrade);
+        assert!(easy.1 >= normal.1 && easy.6 >= normal.6, "{:?}", upgrade);
+        assert!(nightmare.1 <= normal.1 && nightmare.6 <= normal.6, "{:?}", upgrade);
+        // An upgrade that gives something on Normal gives at least one point on Nightmare
+        assert_eq!(normal.1 > 0, nightmare.1 > 0, "{:?}", upgrade);
+        assert_eq!((normal.7, normal.8), (nightmare.7, nightmare.8), "{:?}", upgrade);
+    }
+}
+
+#[test]
+fn permadeath_is_picked_separately_from_the_difficulty() {
+    for difficulty in Difficulty::ALL {
+        for permadeath in [false, true] {
+            let setup = RunSetup { seed: None, difficulty, permadeath };
+            let world = new_run(&RoomGraph::default(), &EncounterTable::default(), &setup);
+            assert_eq!((world.difficulty, world.permadeath), (difficulty, permadeath));
+        }
+    }
+}
+
+#[test]
+fn save_file_keeps_the_difficulty() {
+    let save = SaveGame { difficulty: Difficulty::Hard, permadeath: true };
+    let content = ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default()).expect("serializable");
+    assert_eq!(SaveGame::from_ron(&content), Ok(save));
+    // Older save files with only the run position load as Normal without permadeath
+    assert_eq!(SaveGame::from_ron("(seed: Some(42), path: [0])").map(|save| (save.difficulty, save.permadeath)), Ok((Difficulty::Normal, false)));
+}
@@ -0,0 +1,62 @@
+use informatik_game_bevy::config::Config;
+use informatik_game_bevy::world::{generate_dungeon, EncounterTable, EnemyConfigs, RoomTypeData, WorldState};
+
+fn config_table() -> EncounterTable {
+    let content = std::fs::read_to_string("assets/config/enemies.ron").expect("enemies.ron exists");
+    EncounterTable::from_enemies(&EnemyConfigs::from_ron(&content).expect("valid enemy configs"))
+}
+
+fn rooms_of_kind(world: &WorldState, kind: RoomTypeData) -> usize {
+    world.graph.rooms.iter().filter(|room| room.kind == kind).count()
+}
+
+#[test]
+fn same_seed_gives_same_dungeon() {
+    for seed in [0, 42, 123_456_789] {
+        let first = generate_dungeon(seed, &config_table());
+        let second = generate_dungeon(seed, &config_table());
+        assert_eq!(format!("{:?}", first.graph), format!("{:?}", second.graph), "seed {}", seed);
+        assert_eq!(first.seed, Some(seed));
+    }
+}
+
+#[test]
+fn every_dungeon_has_rest_treasure_and_boss() {
+    for seed in 0..200 {
+        let world = generate_dungeon(seed, &config_table());
+        assert_eq!(world.current_room(), RoomTypeData::IntroRoom, "seed {}", seed);
+        assert_eq!(rooms_of_kind(&world, RoomTypeData::FinalRoom), 1, "seed {}", seed);
+        assert!(rooms_of_kind(&world, RoomTypeData::RestRoom) >= 1, "seed {}", seed);
+        assert!(rooms_of_kind(&world, RoomTypeData::TreasureRoom) >= 1, "seed {}", seed);
+
+        // The boss is the only dead end, so every path leads to it
+        let layers = world.graph.layers();
+        let boss_layer = layers.last().expect("dungeon has layers");
+        assert_eq!(boss_layer.len(), 1, "seed {}", seed);
+        assert_eq!(world.graph.rooms[boss_layer[0]].kind, RoomTypeData::FinalRoom, "seed {}", seed);
+        for (index, room) in world.graph.rooms.iter().enumerate() {
+            assert_eq!(room.exits.is_empty(), index == boss_layer[0], "seed {}, room {}", seed, index);
+        }
+    }
+}
+
+#[test]
+fn every_room_is_reachable() {
+    for seed in 0..200 {
+        let world = generate_dungeon(seed, &config_table());
+        let reachable: usize = world.graph.layers().iter().map(Vec::len).sum();
+        assert_eq!(reachable, world.graph.rooms.len(), "seed {}", seed);
+    }
+}
+
+#[test]
+fn fight_rooms_have_enemies_and_peaceful_rooms_do_not() {
+    for seed in 0..200 {
+        let world = generate_dungeon(seed, &config_table());
+        for room in &world.graph.rooms {
+            let peaceful = matches!(room.kind, RoomTypeData::RestRoom | RoomTypeData::TreasureRoom);
+            assert_eq!(room.enemies.is_empty(), peaceful, "seed {}, {:?}", seed, room.kind);
+            assert!(room.enemies.len() <= 5, "seed {}, {:?}", seed, room.kind);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use informatik_game_bevy::config::Config;
+use informatik_game_bevy::world::{pick_encounter, EncounterBudget, EncounterTable, EnemyConfigs, EnemyTypeData, MAX_ENEMIES};
+
+fn config_table() -> EncounterTable {
+    let content = std::fs::read_to_string("assets/config/enemies.ron").expect("enemies.ron exists");
+    EncounterTable::from_enemies(&EnemyConfigs::from_ron(&content).expect("valid enemy configs"))
+}
+
+#[test]
+fn computed_threat_follows_the_stats() {
+    let table = EncounterTable::default();
+    let threats: Vec<u32> = [
+        EnemyTypeData::MiniZombie,
+        EnemyTypeData::Scientist,
+        EnemyTypeData::BigZombie,
+        EnemyTypeData::Endboss,
+    ].iter().map(|&enemy| table.threat(enemy)).collect();
+    assert!(threats.windows(2).all(|pair| pair[0] < pair[1]), "threats {:?}", threats);
+}
+
+#[test]
+fn config_overrides_threat_and_tags() {
+    let enemies = EnemyConfigs::from_ron(r#"{ "MiniZombie": (threat: 99, tags: ["lab"]) }"#).expect("valid enemy configs");
+    let table = EncounterTable::from_enemies(&enemies);
+    assert_eq!(table.threat(EnemyTypeData::MiniZombie), 99);
+    assert!(table.has_any_tag(EnemyTypeData::MiniZombie, &["lab".to_string()]));
+    assert!(!table.has_any_tag(EnemyTypeData::MiniZombie, &["zombie".to_string()]));
+    assert_eq!(table.threat(EnemyTypeData::Scientist), EnemyTypeData::Scientist.computed_threat());
+
+    assert!(EnemyConfigs::from_ron(r#"{ "Dragon": (threat: 5) }"#).is_err());
+}
+
+#[test]
+fn encounters_fit_budget_and_tags() {
+    let table = config_table();
+    let mut rng = StdRng::seed_from_u64(7);
+    for budget in [10, 20, 30, 60] {
+        let encounter = EncounterBudget::new(budget, &["zombie"]);
+        for _ in 0..50 {
+            let enemies = pick_encounter(&encounter, &table, &mut rng);
+            assert!(!enemies.is_empty() && enemies.len() <= MAX_ENEMIES, "{:?}", enemies);
+            assert!(table.encounter_threat(&enemies) <= budget, "budget {}: {:?}", budget, enemies);
+            assert!(enemies.iter().all(|&enemy| table.has_any_tag(enemy, &encounter.tags)), "{:?}", enemies);
+        }
+    }
+}
+
+#[test]
+fn too_small_budget_still_gives_the_weakest_enemy() {
+    let table = config_table();
+    let mut rng = StdRng::seed_from_u64(1);
+    let enemies = pick_encounter(&EncounterBudget::new(1, &["brute"]), &table, &mut rng);
+    assert_eq!(enemies, vec![EnemyTypeData::BigZombie]);
+}
+
+#[test]
+fn tags_only_come_from_the_config() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let zombies = EncounterBudget::new(30, &["zombie"]);
+    assert!(pick_encounter(&zombies, &EncounterTable::default(), &mut rng).is_empty());
+    assert!(!EncounterTable::default().has_any_tag(EnemyTypeData::MiniZombie, &zombies.tags));
+    assert!(config_table().has_any_tag(EnemyTypeData::MiniZombie, &zombies.tags));
+}
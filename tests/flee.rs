@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use informatik_game_bevy::character::{Health, PlayerBundle};
+use informatik_game_bevy::combat::{execute_flee_system, BattleState, CombatEndEvent, CombatMessageEvent, FleeAttempt};
+use informatik_game_bevy::localization::{Localization, LOCALE_DIR};
+use informatik_game_bevy::world::EnemyTypeData;
+
+// Only the flee system, with a player waiting for input and the given enemies (base initiative, alive)
+fn flee_app(player_initiative: u32, enemies: &[(u32, bool)], flee_allowed: bool) -> (App, Entity) {
+    let mut app = App::new();
+    let loc = Localization::load(LOCALE_DIR);
+    app.add_message::<CombatMessageEvent>()
+        .add_message::<CombatEndEvent>()
+        .insert_resource(BattleState {
+            combat_active: true,
+            flee_allowed,
+            waiting_for_player_input: true,
+            ..default()
+        })
+        .add_systems(Update, execute_flee_system);
+
+    let player = app.world_mut()
+        .spawn((PlayerBundle::new("Spieler".to_string(), 20, 1, player_initiative, 12, 10, 3, 2, 1, 1), FleeAttempt))
+        .id();
+    let mut turn_queue = vec![player];
+    for &(initiative, alive) in enemies {
+        let mut enemy = EnemyTypeData::MiniZombie.bundle(&loc);
+        enemy.initiative.base = initiative;
+        if !alive {
+            enemy.health = Health { current: 0, max: enemy.health.max };
+        }
+        turn_queue.push(app.world_mut().spawn(enemy).id());
+    }
+    app.world_mut().resource_mut::<BattleState>().turn_queue = turn_queue;
+    app.insert_resource(loc);
+    app.update();
+    (app, player)
+}
+
+fn messages(app: &mut App) -> Vec<String> {
+    app.world_mut().resource_mut::<Messages<CombatMessageEvent>>().drain().map(|event| event.message).collect()
+}
+
+fn ends(app: &mut App) -> Vec<(bool, bool)> {
+    app.world_mut().resource_mut::<Messages<CombatEndEvent>>().drain()
+        .map(|event| (event.player_won, event.player_fled))
+        .collect()
+}
+
+#[test]
+fn outrunning_the_fastest_living_enemy_ends_the_fight() {
+    // The dead enemy would always win the roll, only the living one counts
+    let (mut app, player) = flee_app(100, &[(200, false), (1, true)], true);
+    assert!(!app.world().entity(player).contains::<FleeAttempt>());
+    assert_eq!(ends(&mut app), vec![(false, true)]);
+    assert!(!app.world().resource::<BattleState>().combat_active);
+    let success = app.world().resource::<Localization>().t("combat.flee_success");
+    let success_start = success.split('(').next().expect("text before the rolls");
+    assert!(messages(&mut app)[0].starts_with(success_start));
+}
+
+#[test]
+fn a_failed_flee_costs_the_turn() {
+    let (mut app, player) = flee_app(1, &[(1, true), (100, true)], true);
+    assert!(!app.world().entity(player).contains::<FleeAttempt>());
+    assert!(ends(&mut app).is_empty());
+    let battle_state = app.world().resource::<BattleState>();
+    assert!(battle_state.combat_active);
+    assert_eq!(battle_state.current_turn_index, 1);
+    assert!(!battle_state.waiting_for_player_input);
+}
+
+#[test]
+fn a_forbidden_flee_keeps_the_turn() {
+    let (mut app, player) = flee_app(100, &[(1, true)], false);
+    assert!(!app.world().entity(player).contains::<FleeAttempt>());
+    assert!(ends(&mut app).is_empty());
+    let forbidden = app.world().resource::<Localization>().t("combat.flee_forbidden");
+    assert_eq!(messages(&mut app), vec![forbidden]);
+    let battle_state = app.world().resource::<BattleState>();
+    assert!(battle_state.combat_active);
+    assert_eq!(battle_state.current_turn_index, 0);
+    assert!(battle_state.waiting_for_player_input);
+}
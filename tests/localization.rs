@@ -0,0 +1,86 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use informatik_game_bevy::localization::{Language, Localization, LOCALE_DIR};
+
+#[test]
+fn every_language_has_every_key() {
+    let localization = Localization::load(LOCALE_DIR);
+    for language in Language::ALL {
+        let missing = localization.missing_keys(language);
+        assert!(missing.is_empty(), "{:?} string table is missing {:?}", language, missing);
+    }
+}
+
+#[test]
+fn every_key_used_in_the_source_exists() {
+    let localization = Localization::load(LOCALE_DIR);
+    let mut literals = Vec::new();
+    collect_string_literals(Path::new("src"), &mut literals);
+
+    // Only literals that look like "namespace.id" with a namespace the tables use
+    let namespaces: BTreeSet<String> = literals.iter()
+        .filter(|literal| localization.has_key(Language::German, literal))
+        .filter_map(|key| key.split('.').next().map(str::to_string))
+        .collect();
+    assert!(!namespaces.is_empty(), "no localization keys found in src");
+
+    for key in literals.iter().filter(|literal| looks_like_key(literal, &namespaces)) {
+        for language in Language::ALL {
+            assert!(localization.has_key(language, key), "key \"{}\" is missing in {:?}", key, language);
+        }
+    }
+}
+
+fn looks_like_key(literal: &str, namespaces: &BTreeSet<String>) -> bool {
+    literal.contains('.')
+        && literal.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+        && literal.split('.').all(|part| !part.is_empty())
+        && literal.split('.').next().is_some_and(|namespace| namespaces.contains(namespace))
+}
+
+fn collect_string_literals(dir: &Path, literals: &mut Vec<String>) {
+    for entry in std::fs::read_dir(dir).expect("src directory is readable") {
+        let path = entry.expect("directory entry").path();
+        if path.is_dir() {
+            collect_string_literals(&path, literals);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            let source = std::fs::read_to_string(&path).expect("source file is readable");
+            literals.extend(string_literals(&source));
+        }
+    }
+}
+
+// Contents of all "..." literals outside of comments, good enough for keys (no raw strings)
+fn string_literals(source: &str) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            // Char literals like '"' must not open a string
+            '\'' => {
+                let quoted: String = chars.clone().take(3).collect();
+                if quoted.starts_with("\"'") {
+                    chars.nth(1);
+                }
+            }
+            '"' => {
+                let mut literal = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => literal.push(c),
+                    }
+                }
+                literals.push(literal);
+            }
+            _ => {}
+        }
+    }
+    literals
+}
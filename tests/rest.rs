@@ -0,0 +1,55 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use informatik_game_bevy::character::Health;
+use informatik_game_bevy::config::Config;
+use informatik_game_bevy::world::{CampOutcome, RestRules};
+
+#[test]
+fn config_file_parses_and_partial_files_keep_defaults() {
+    let content = std::fs::read_to_string("assets/config/rest.ron").expect("rest.ron exists");
+    RestRules::from_ron(&content).expect("valid rest rules");
+
+    let rules = RestRules::from_ron("(camp_uses: 5)").expect("valid rest rules");
+    assert_eq!(rules.camp_uses, 5);
+    assert_eq!(rules.rest_room_heal_percent, RestRules::default().rest_room_heal_percent);
+}
+
+#[test]
+fn rest_room_heals_a_share_of_max_hp() {
+    let rules = RestRules { rest_room_heal_percent: 50, ..RestRules::default() };
+    let mut health = Health::new(30);
+    health.current = 5;
+    assert_eq!(rules.rest(&mut health), 15);
+    assert_eq!(health.current, 20);
+
+    // Never above max
+    assert_eq!(rules.rest(&mut health), 10);
+    assert_eq!(health.current, 30);
+}
+
+#[test]
+fn camps_run_out() {
+    let rules = RestRules { camp_uses: 2, camp_heal_percent: 10, ambush_chance_percent: 0, ..RestRules::default() };
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut health = Health::new(40);
+    health.current = 1;
+    let mut camps_left = rules.camp_uses;
+
+    assert_eq!(rules.camp(&mut camps_left, &mut health, &mut rng), CampOutcome::Rested { healed: 4 });
+    assert_eq!(rules.camp(&mut camps_left, &mut health, &mut rng), CampOutcome::Rested { healed: 4 });
+    assert_eq!(rules.camp(&mut camps_left, &mut health, &mut rng), CampOutcome::NoCampsLeft);
+    assert_eq!((camps_left, health.current), (0, 9));
+}
+
+#[test]
+fn ambush_hurts_but_never_kills() {
+    let rules = RestRules { ambush_chance_percent: 100, ambush_damage_percent: 50, ..RestRules::default() };
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut health = Health::new(20);
+    health.current = 3;
+    let mut camps_left = 2;
+
+    assert_eq!(rules.camp(&mut camps_left, &mut health, &mut rng), CampOutcome::Ambushed { damage: 2 });
+    assert_eq!(rules.camp(&mut camps_left, &mut health, &mut rng), CampOutcome::Ambushed { damage: 0 });
+    assert_eq!((camps_left, health.current), (0, 1));
+}
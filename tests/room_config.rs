@@ -0,0 +1,40 @@
+use informatik_game_bevy::config::Config;
+use informatik_game_bevy::world::{EnemyTypeData, FleeRule, RoomGraph, RoomTypeData, WorldState};
+
+fn room_of_kind(graph: &RoomGraph, kind: RoomTypeData) -> usize {
+    graph.rooms.iter().position(|room| room.kind == kind).expect("room in graph")
+}
+
+#[test]
+fn flee_rules_come_from_the_config() {
+    let content = std::fs::read_to_string("assets/config/rooms.ron").expect("rooms.ron exists");
+    let graph = RoomGraph::from_ron(&content).expect("valid room graph");
+    assert_eq!(graph.rooms[room_of_kind(&graph, RoomTypeData::FinalRoom)].flee, FleeRule::Forbidden);
+    assert_eq!(graph.rooms[room_of_kind(&graph, RoomTypeData::Pantry1)].flee, FleeRule::ToNext);
+
+    // Rooms without a flee field can't be fled from, whatever their type
+    let graph = RoomGraph::from_ron("[(id: FloorRoom, flee: ToNext), (id: Corridor)]").expect("valid room graph");
+    assert_eq!(graph.rooms[0].flee, FleeRule::ToNext);
+    assert_eq!(graph.rooms[1].flee, FleeRule::Forbidden);
+}
+
+#[test]
+fn reinforcements_come_from_the_config() {
+    let content = std::fs::read_to_string("assets/config/rooms.ron").expect("rooms.ron exists");
+    let graph = RoomGraph::from_ron(&content).expect("valid room graph");
+    let dining_hall = &graph.rooms[room_of_kind(&graph, RoomTypeData::DiningHall)];
+    let reinforcements = dining_hall.reinforcements.as_ref().expect("dining hall gets reinforcements");
+    assert_eq!(reinforcements.after_round, 3);
+    assert_eq!(reinforcements.enemies, vec![EnemyTypeData::MiniZombie, EnemyTypeData::MiniZombie]);
+    assert_eq!(reinforcements.message, "room.dining_hall.reinforcements");
+    assert!(graph.rooms[room_of_kind(&graph, RoomTypeData::IntroRoom)].reinforcements.is_none());
+}
+
+#[test]
+fn fleeing_needs_a_room_to_flee_into() {
+    let graph = RoomGraph::from_ron("[(id: FloorRoom, flee: ToNext), (id: Corridor)]").expect("valid room graph");
+    assert!(!WorldState::from_graph(graph).can_flee(), "no door to take");
+    let graph = RoomGraph::from_ron("[(id: FloorRoom, flee: ToPrevious, exits: [(to: Corridor, hint: \"door.corridor.final\")]), (id: Corridor)]")
+        .expect("valid room graph");
+    assert!(WorldState::from_graph(graph).can_flee(), "the start room falls back to its first door");
+}
@@ -0,0 +1,269 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use informatik_game_bevy::character::{Enemy, Health, Initiative, Player};
+use informatik_game_bevy::input::{ActionState, CharacterSelection, GameAction, InputBindings, InputDevice};
+use informatik_game_bevy::localization::{Localization, LOCALE_DIR};
+use informatik_game_bevy::ui::StoryText;
+use informatik_game_bevy::world::{self, retry_room_system, RestRules, RoomTypeData, RunStats, WorldState};
+use informatik_game_bevy::combat::{BattleState, FleeAttempt};
+use informatik_game_bevy::{combat, GameState};
+
+// Game without window and UI: combat and world systems with scripted input
+fn test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .init_state::<GameState>()
+        .insert_resource(Localization::load(LOCALE_DIR))
+        .init_resource::<StoryText>()
+        .init_resource::<ActionState>()
+        .init_resource::<InputBindings>()
+        .init_resource::<InputDevice>()
+        .init_resource::<CharacterSelection>()
+        .add_plugins((combat::plugin, world::plugin));
+    app.update();
+    app
+}
+
+// State changes take effect at the start of the next frame
+fn go_to(app: &mut App, state: GameState) {
+    app.world_mut().resource_mut::<NextState<GameState>>().set(state);
+    app.update();
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+fn start_run(app: &mut App) {
+    go_to(app, GameState::CharacterSelection);
+    go_to(app, GameState::Gameplay);
+}
+
+// Show the whole story text and press the action for one frame
+fn press(app: &mut App, action: GameAction) {
+    app.world_mut().resource_mut::<StoryText>().reveal_all();
+    app.world_mut().resource_mut::<ActionState>().set_just_pressed(&[action]);
+    app.update();
+    app.world_mut().resource_mut::<ActionState>().set_just_pressed(&[]);
+    app.update();
+}
+
+fn confirm(app: &mut App) {
+    press(app, GameAction::Confirm);
+}
+
+// Confirm the fight and set health before the first turn, so the first combat frame decides it
+fn start_fight(app: &mut App, before_first_turn: impl FnOnce(&mut App)) {
+    app.world_mut().resource_mut::<StoryText>().reveal_all();
+    app.world_mut().resource_mut::<ActionState>().set_just_pressed(&[GameAction::Confirm]);
+    app.update();
+    app.world_mut().resource_mut::<ActionState>().set_just_pressed(&[]);
+    before_first_turn(app);
+    app.update();
+}
+
+// Flee on the player's turn, with initiative 100 the roll can't fail
+fn flee(app: &mut App) {
+    let player = players(app)[0].0;
+    app.world_mut().entity_mut(player).insert(FleeAttempt);
+    app.update();
+    app.update();
+}
+
+fn players(app: &mut App) -> Vec<(Entity, u32, u32)> {
+    app.world_mut()
+        .query_filtered::<(Entity, &Health), With<Player>>()
+        .iter(app.world())
+        .map(|(entity, health)| (entity, health.current, health.max))
+        .collect()
+}
+
+fn set_enemy_health(app: &mut App, current: u32) {
+    let mut query = app.world_mut().query_filtered::<&mut Health, With<Enemy>>();
+    for mut health in query.iter_mut(app.world_mut()) {
+        health.current = current;
+    }
+}
+
+fn set_player_health(app: &mut App, current: u32) {
+    let mut query = app.world_mut().query_filtered::<&mut Health, With<Player>>();
+    for mut health in query.iter_mut(app.world_mut()) {
+        health.current = current;
+    }
+}
+
+#[test]
+fn player_keeps_damage_and_upgrades_through_several_rooms() {
+    let mut app = test_app();
+    start_run(&mut app);
+    let [(player, _, _)] = players(&mut app)[..] else {
+        panic!("expected exactly one player after the run started");
+    };
+
+    for room in 0..4 {
+        assert_eq!(state(&app), GameState::Gameplay, "room {}", room);
+        let world = app.world().resource::<WorldState>();
+        let fight = !world.current().enemies.is_empty();
+        let upgrade_life = world.current().upgrade
+            .map(|upgrade| world.difficulty.upgrade_stats(upgrade).1)
+            .unwrap_or(0);
+        let depth = world.depth();
+
+        // Hurt the player a bit, the wound has to survive the room change
+        let (_, _, max_before) = players(&mut app)[0];
+        set_player_health(&mut app, max_before - 5);
+
+        if fight {
+            start_fight(&mut app, |app| set_enemy_health(app, 0));
+            assert_eq!(state(&app), GameState::Combat, "room {}", room);
+            assert_eq!(players(&mut app).first().map(|p| p.0), Some(player), "player despawned entering combat");
+            app.update();
+        } else {
+            confirm(&mut app);
+        }
+
+        assert_eq!(state(&app), GameState::Gameplay, "room {}", room);
+        let [(entity, current, max)] = players(&mut app)[..] else {
+            panic!("expected exactly one player after room {}", room);
+        };
+        assert_eq!(entity, player, "player was replaced in room {}", room);
+        assert_eq!(max, max_before + upgrade_life, "upgrade lost in room {}", room);
+        assert!(current > 0 && current <= max_before - 5 + upgrade_life, "healed to {} in room {}", current, room);
+
+        // Through the first door
+        confirm(&mut app);
+        assert_eq!(app.world().resource::<WorldState>().depth(), depth + 1, "room {}", room);
+    }
+}
+
+#[test]
+fn defeat_and_retry_keep_the_same_player() {
+    let mut app = test_app();
+    start_run(&mut app);
+    let player = players(&mut app)[0].0;
+    assert!(!app.world().resource::<WorldState>().current().enemies.is_empty(), "start room has a fight");
+
+    // Enter the fight hurt, the retry gives back exactly that much
+    set_player_health(&mut app, 7);
+    start_fight(&mut app, |app| set_player_health(app, 0));
+    app.update();
+    assert_eq!(state(&app), GameState::GameOver);
+
+    app.world_mut().run_system_cached(retry_room_system).expect("retry runs");
+    go_to(&mut app, GameState::Gameplay);
+    let [(entity, current, _)] = players(&mut app)[..] else {
+        panic!("expected exactly one player after the retry");
+    };
+    assert_eq!(entity, player);
+    assert_eq!(current, 7);
+}
+
+#[test]
+fn back_to_the_menu_ends_the_run() {
+    let mut app = test_app();
+    start_run(&mut app);
+    assert_eq!(players(&mut app).len(), 1);
+
+    confirm(&mut app);
+    assert_eq!(state(&app), GameState::Combat);
+    go_to(&mut app, GameState::MainMenu);
+    app.update();
+    assert!(players(&mut app).is_empty());
+    assert_eq!(app.world_mut().query_filtered::<(), With<Enemy>>().iter(app.world()).count(), 0);
+
+    // A new run gets a new player, not a second one
+    start_run(&mut app);
+    assert_eq!(players(&mut app).len(), 1);
+}
+
+#[test]
+fn camping_after_a_fight_heals_until_the_camps_run_out() {
+    let mut app = test_app();
+    app.insert_resource(RestRules { camp_uses: 1, camp_heal_percent: 50, ambush_chance_percent: 0, ..RestRules::default() });
+    start_run(&mut app);
+    assert_eq!(app.world().resource::<WorldState>().camps_left, 1);
+
+    start_fight(&mut app, |app| set_enemy_health(app, 0));
+    app.update();
+    assert!(app.world().resource::<WorldState>().door_choice.is_some(), "doors are shown after the fight");
+
+    set_player_health(&mut app, 1);
+    press(&mut app, GameAction::Camp);
+    let (_, current, max) = players(&mut app)[0];
+    assert_eq!(current, (1 + max.div_ceil(2)).min(max));
+    assert_eq!(app.world().resource::<WorldState>().camps_left, 0);
+
+    press(&mut app, GameAction::Camp);
+    assert_eq!(players(&mut app)[0].1, current, "no camps left");
+}
+
+#[test]
+fn fleeing_back_into_a_won_room_gives_no_second_reward() {
+    let mut app = test_app();
+    start_run(&mut app);
+    start_fight(&mut app, |app| set_enemy_health(app, 0));
+    app.update();
+    let upgrades = app.world().resource::<RunStats>().upgrades_taken;
+    let (_, _, max) = players(&mut app)[0];
+
+    // Into the floor room, which lets the player flee back
+    confirm(&mut app);
+    assert_eq!(app.world().resource::<WorldState>().depth(), 2);
+    // The player acts first and flees before any enemy has queued an attack
+    start_fight(&mut app, |app| {
+        let mut query = app.world_mut().query_filtered::<&mut Initiative, With<Player>>();
+        for mut initiative in query.iter_mut(app.world_mut()) {
+            initiative.base = 100;
+        }
+    });
+    assert_eq!(state(&app), GameState::Combat);
+    assert!(app.world().resource::<BattleState>().waiting_for_player_input);
+    flee(&mut app);
+
+    assert_eq!(state(&app), GameState::Gameplay);
+    let world = app.world().resource::<WorldState>();
+    assert_eq!(world.depth(), 1);
+    assert_eq!(world.door_choice, Some(0), "the won room only offers its doors");
+    let fled_text = app.world().resource::<Localization>().t("story.fled_back");
+    let mut story = app.world_mut().resource_mut::<StoryText>();
+    story.reveal_all();
+    assert_eq!(story.visible_text().lines().next(), fled_text.lines().next(), "flee text is kept");
+
+    // Confirm goes through the door again instead of starting a fight
+    confirm(&mut app);
+    assert_eq!(state(&app), GameState::Gameplay);
+    assert_eq!(app.world().resource::<WorldState>().depth(), 2);
+    assert_eq!(app.world().resource::<RunStats>().upgrades_taken, upgrades);
+    assert_eq!(players(&mut app)[0].2, max);
+}
+
+#[test]
+fn fleeing_forward_shows_the_next_room_before_its_fight() {
+    let mut app = test_app();
+    start_run(&mut app);
+    start_fight(&mut app, |app| set_enemy_health(app, 0));
+    app.update();
+
+    // Second door into the pantry, which lets the player flee through its side exit
+    press(&mut app, GameAction::SelectN(2));
+    assert_eq!(app.world().resource::<WorldState>().current_room(), RoomTypeData::Pantry1);
+    start_fight(&mut app, |app| {
+        let mut query = app.world_mut().query_filtered::<&mut Initiative, With<Player>>();
+        for mut initiative in query.iter_mut(app.world_mut()) {
+            initiative.base = 100;
+        }
+    });
+    assert!(app.world().resource::<BattleState>().waiting_for_player_input);
+    flee(&mut app);
+    assert_eq!(state(&app), GameState::Gameplay);
+    assert_eq!(app.world().resource::<WorldState>().current_room(), RoomTypeData::DiningHall);
+
+    // Confirm on the flee text shows the dining hall story, the next Confirm starts its fight
+    confirm(&mut app);
+    assert_eq!(state(&app), GameState::Gameplay);
+    let story = app.world().resource::<Localization>().t(RoomTypeData::DiningHall.story_key());
+    app.world_mut().resource_mut::<StoryText>().reveal_all();
+    assert_eq!(app.world().resource::<StoryText>().visible_text(), story);
+    confirm(&mut app);
+    assert_eq!(state(&app), GameState::Combat);
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use informatik_game_bevy::character::{Enemy, Health};
+use informatik_game_bevy::input::{ActionState, CharacterSelection, GameAction, InputBindings, InputDevice};
+use informatik_game_bevy::localization::{Localization, LOCALE_DIR};
+use informatik_game_bevy::ui::StoryText;
+use informatik_game_bevy::world::{self, RunStats};
+use informatik_game_bevy::{combat, GameState};
+
+// Game without window and UI, like in run_lifecycle.rs
+fn test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .init_state::<GameState>()
+        .insert_resource(Localization::load(LOCALE_DIR))
+        .init_resource::<StoryText>()
+        .init_resource::<ActionState>()
+        .init_resource::<InputBindings>()
+        .init_resource::<InputDevice>()
+        .init_resource::<CharacterSelection>()
+        .add_plugins((combat::plugin, world::plugin));
+    app.update();
+    app
+}
+
+fn go_to(app: &mut App, state: GameState) {
+    app.world_mut().resource_mut::<NextState<GameState>>().set(state);
+    app.update();
+}
+
+#[test]
+fn hits_count_for_the_right_side() {
+    let mut stats = RunStats::default();
+    stats.record_hit(7, true, false);
+    stats.record_hit(4, false, true);
+    stats.record_hit(3, false, false); // enemy on enemy, e.g. a boss hitting a summon
+    assert_eq!((stats.damage_dealt, stats.damage_taken), (7, 4));
+}
+
+#[test]
+fn summary_shows_every_number() {
+    let stats = RunStats {
+        rooms_cleared: 3,
+        enemies_killed: 11,
+        damage_dealt: 123,
+        damage_taken: 45,
+        rounds_fought: 17,
+        upgrades_taken: 2,
+        time_played: Duration::from_secs(754),
+    };
+    assert_eq!(stats.time_text(), "12:34");
+
+    let summary = stats.summary(&Localization::load(LOCALE_DIR));
+    for number in ["3", "11", "123", "45", "17", "2", "12:34"] {
+        assert!(summary.contains(number), "{} missing in {:?}", number, summary);
+    }
+    assert!(!summary.contains('{'), "unfilled placeholder in {:?}", summary);
+}
+
+#[test]
+fn a_fight_won_in_the_first_round_counts_one_round() {
+    let mut app = test_app();
+    go_to(&mut app, GameState::CharacterSelection);
+    go_to(&mut app, GameState::Gameplay);
+
+    // Confirm the fight, then kill the enemies before the first turn
+    app.world_mut().resource_mut::<StoryText>().reveal_all();
+    app.world_mut().resource_mut::<ActionState>().set_just_pressed(&[GameAction::Confirm]);
+    app.update();
+    app.world_mut().resource_mut::<ActionState>().set_just_pressed(&[]);
+    let mut enemies = app.world_mut().query_filtered::<&mut Health, With<Enemy>>();
+    for mut health in enemies.iter_mut(app.world_mut()) {
+        health.current = 0;
+    }
+    app.update();
+    app.update();
+
+    assert_eq!(*app.world().resource::<State<GameState>>().get(), GameState::Gameplay, "fight won");
+    assert_eq!(app.world().resource::<RunStats>().rounds_fought, 1);
+}
@@ -0,0 +1,30 @@
+use informatik_game_bevy::config::Config;
+use informatik_game_bevy::world::{ClassConfigs, EnemyConfigs, EnemyTypeData, PlayerTypeData, SpriteSheets};
+
+#[test]
+fn sprites_come_from_the_configs() {
+    let mut sprites = SpriteSheets::default();
+    let enemies = std::fs::read_to_string("assets/config/enemies.ron").expect("enemies.ron exists");
+    let classes = std::fs::read_to_string("assets/config/classes.ron").expect("classes.ron exists");
+    sprites.load_enemies(&EnemyConfigs::from_ron(&enemies).expect("valid enemy configs"));
+    sprites.load_classes(&ClassConfigs::from_ron(&classes).expect("valid class configs"));
+    assert_eq!(sprites.enemy(EnemyTypeData::Endboss).path, "textures/enemies/endboss.png");
+    assert_eq!(sprites.player(PlayerTypeData::ShieldFighter).path, "textures/player/shield_fighter.png");
+}
+
+#[test]
+fn entries_without_sprite_share_the_fallback_rectangle() {
+    let mut sprites = SpriteSheets::default();
+    sprites.load_enemies(&EnemyConfigs::from_ron(r#"{
+        "MiniZombie": (sprite: Some((path: "textures/enemies/other.png", fallback_color: (1.0, 0.0, 0.0)))),
+        "Scientist": (tags: ["lab"]),
+    }"#).expect("valid enemy configs"));
+    assert_eq!(sprites.enemy(EnemyTypeData::MiniZombie).path, "textures/enemies/other.png");
+
+    // No built-in sheets: no texture, one color for all of them
+    let scientist = sprites.enemy(EnemyTypeData::Scientist);
+    let unconfigured = [scientist.clone(), sprites.enemy(EnemyTypeData::Endboss), sprites.player(PlayerTypeData::SwordFighter)];
+    assert!(unconfigured.iter().all(|sheet| sheet.path.is_empty()));
+    assert!(unconfigured.iter().all(|sheet| sheet.fallback_color == scientist.fallback_color));
+    assert!(ClassConfigs::from_ron(r#"{ "Wizard": () }"#).is_err());
+}